@@ -17,6 +17,7 @@ pub mod error;
 pub mod log;
 pub mod log_level;
 pub mod monitoring;
+pub mod time;
 pub mod types;
 
 // Re‑exports for ergonomic access:
@@ -27,3 +28,4 @@ pub use core_types::logging::LogMessage;
 pub use error::RustecalError;
 pub use log::Log;
 pub use log_level::LogLevel;
+pub use time::Time;
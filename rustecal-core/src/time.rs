@@ -0,0 +1,21 @@
+//! eCAL time functions.
+//!
+//! This module wraps the C API from `ecal_c/time.h`. The eCAL time is the time
+//! base of send timestamps and may be synchronized between hosts by a time
+//! plugin, so it should be preferred over the system clock when comparing
+//! against `Received::timestamp`.
+
+/// Provides access to the current eCAL time.
+pub struct Time;
+
+impl Time {
+    /// Returns the current eCAL time in microseconds since epoch.
+    pub fn get_microseconds() -> i64 {
+        unsafe { rustecal_sys::eCAL_Time_GetMicroSeconds() }
+    }
+
+    /// Returns the current eCAL time in nanoseconds since epoch.
+    pub fn get_nanoseconds() -> i64 {
+        unsafe { rustecal_sys::eCAL_Time_GetNanoSeconds() }
+    }
+}
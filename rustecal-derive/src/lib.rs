@@ -0,0 +1,267 @@
+//! # rustecal-derive
+//!
+//! Derive macros for message types used with rustecal's `TypedPublisher` and
+//! `TypedSubscriber`.
+//!
+//! `#[derive(EcalMessage)]` implements `PublisherMessage` and `SubscriberMessage`
+//! directly on your type, so no wrapper struct or hand-written impl is needed.
+//!
+//! ## Attributes
+//!
+//! All attributes go into a single or multiple `#[ecal(...)]` lists:
+//!
+//! - `format = "json" | "cbor" | "msgpack" | "protobuf"` – wire format (default: `json`).
+//!   Serde formats use `rustecal-types-serde`, `protobuf` uses `rustecal-types-protobuf`
+//!   and `prost`, so these crates must be dependencies of your crate.
+//! - `encoding = "..."` – overrides the encoding label of the format.
+//! - `type_name = "..."` – explicit, fully qualified type name. Defaults to the Rust
+//!   module path of the type for Serde formats and the protobuf full name for `protobuf`.
+//! - `descriptor = "path"` – embeds the file at `path` (relative to `CARGO_MANIFEST_DIR`)
+//!   as descriptor.
+//! - `descriptor_fn = "path::to::fn"` – calls `fn() -> Vec<u8>` to build the descriptor.
+//! - `schema` – uses the JSON Schema of the type as descriptor (Serde formats only,
+//!   requires the `schema` feature of `rustecal-types-serde`).
+//! - `crate = "path"` – path to `rustecal-pubsub` (default: `::rustecal_pubsub`),
+//!   e.g. `"::rustecal::pubsub"` when only depending on `rustecal`.
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use rustecal_pubsub::EcalMessage;
+//!
+//! #[derive(Serialize, Deserialize, Clone, EcalMessage)]
+//! #[ecal(format = "cbor", type_name = "robot.drive.Status")]
+//! struct Status {
+//!     speed: f32,
+//! }
+//!
+//! let publisher = TypedPublisher::<Status>::new("status")?;
+//! ```
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{DeriveInput, LitStr, Path, parse_macro_input};
+
+/// Implements `PublisherMessage` and `SubscriberMessage` for a struct or enum.
+///
+/// See the [crate documentation](crate) for the supported `#[ecal(...)]` attributes.
+#[proc_macro_derive(EcalMessage, attributes(ecal))]
+pub fn derive_ecal_message(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Wire format selected by `#[ecal(format = "...")]`.
+enum Format {
+    Json,
+    Cbor,
+    Msgpack,
+    Protobuf,
+}
+
+/// Where the descriptor comes from.
+enum Descriptor {
+    File(LitStr),
+    Function(Path),
+    Schema,
+}
+
+/// Parsed `#[ecal(...)]` attributes.
+struct Options {
+    format: Format,
+    encoding: Option<LitStr>,
+    type_name: Option<LitStr>,
+    descriptor: Option<Descriptor>,
+    krate: Path,
+}
+
+impl Options {
+    fn parse(input: &DeriveInput) -> syn::Result<Self> {
+        let mut options = Options {
+            format: Format::Json,
+            encoding: None,
+            type_name: None,
+            descriptor: None,
+            krate: syn::parse_quote!(::rustecal_pubsub),
+        };
+
+        for attr in input.attrs.iter().filter(|a| a.path().is_ident("ecal")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("format") {
+                    let value: LitStr = meta.value()?.parse()?;
+                    options.format = match value.value().as_str() {
+                        "json" => Format::Json,
+                        "cbor" => Format::Cbor,
+                        "msgpack" => Format::Msgpack,
+                        "protobuf" | "proto" => Format::Protobuf,
+                        other => {
+                            return Err(syn::Error::new(
+                                value.span(),
+                                format!(
+                                    "unknown format `{other}`, expected one of: json, cbor, msgpack, protobuf"
+                                ),
+                            ));
+                        }
+                    };
+                } else if meta.path.is_ident("encoding") {
+                    options.encoding = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("type_name") {
+                    options.type_name = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("descriptor") {
+                    options.set_descriptor(&meta, Descriptor::File(meta.value()?.parse()?))?;
+                } else if meta.path.is_ident("descriptor_fn") {
+                    let value: LitStr = meta.value()?.parse()?;
+                    options.set_descriptor(&meta, Descriptor::Function(value.parse()?))?;
+                } else if meta.path.is_ident("schema") {
+                    options.set_descriptor(&meta, Descriptor::Schema)?;
+                } else if meta.path.is_ident("crate") {
+                    let value: LitStr = meta.value()?.parse()?;
+                    options.krate = value.parse()?;
+                } else {
+                    return Err(meta.error("unsupported `ecal` attribute"));
+                }
+                Ok(())
+            })?;
+        }
+
+        if matches!(options.format, Format::Protobuf)
+            && matches!(options.descriptor, Some(Descriptor::Schema))
+        {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "`schema` is only supported for serde formats",
+            ));
+        }
+
+        Ok(options)
+    }
+
+    fn set_descriptor(
+        &mut self,
+        meta: &syn::meta::ParseNestedMeta,
+        descriptor: Descriptor,
+    ) -> syn::Result<()> {
+        if self.descriptor.is_some() {
+            return Err(
+                meta.error("only one of `descriptor`, `descriptor_fn` or `schema` may be given")
+            );
+        }
+        self.descriptor = Some(descriptor);
+        Ok(())
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let options = Options::parse(input)?;
+    let krate = &options.krate;
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // Subscriber impl needs an extra buffer lifetime in front of the type's own generics
+    let mut sub_generics = input.generics.clone();
+    sub_generics
+        .params
+        .insert(0, syn::parse_quote!('__ecal_buf));
+    let (sub_impl_generics, _, _) = sub_generics.split_for_impl();
+
+    // Format specific defaults and (de)serialization
+    let (base_datatype, encode, decode, extra) = match options.format {
+        Format::Json | Format::Cbor | Format::Msgpack => {
+            let support = match options.format {
+                Format::Json => quote!(::rustecal_types_serde::json_message::JsonSupport),
+                Format::Cbor => quote!(::rustecal_types_serde::cbor_message::CborSupport),
+                _ => quote!(::rustecal_types_serde::msgpack_message::MsgpackSupport),
+            };
+            let name = ident.to_string();
+            (
+                quote! {
+                    #krate::DataTypeInfo {
+                        encoding: <#support as ::rustecal_types_serde::format_support::FormatSupport>::ENCODING.to_string(),
+                        type_name: ::std::format!("{}::{}", ::std::module_path!(), #name),
+                        descriptor: ::std::vec::Vec::new(),
+                    }
+                },
+                quote! {
+                    <#support as ::rustecal_types_serde::format_support::FormatSupport>::encode(self)
+                        .map(::std::sync::Arc::from)
+                },
+                quote! {
+                    <#support as ::rustecal_types_serde::format_support::FormatSupport>::decode(bytes)
+                },
+                quote!(),
+            )
+        }
+        Format::Protobuf => (
+            quote! {
+                <::rustecal_types_protobuf::ProtobufMessage<Self> as #krate::PublisherMessage>::datatype()
+            },
+            quote! {
+                ::std::result::Result::Ok(::std::sync::Arc::from(::prost::Message::encode_to_vec(self)))
+            },
+            quote! {
+                <Self as ::prost::Message>::decode(bytes).ok()
+            },
+            quote! {
+                impl #impl_generics ::rustecal_types_protobuf::IsProtobufType for #ident #ty_generics #where_clause {}
+            },
+        ),
+    };
+
+    // Attribute overrides on top of the format defaults
+    let encoding = options
+        .encoding
+        .as_ref()
+        .map(|e| quote!(info.encoding = ::std::string::String::from(#e);));
+    let type_name = options
+        .type_name
+        .as_ref()
+        .map(|t| quote!(info.type_name = ::std::string::String::from(#t);));
+    let descriptor = options.descriptor.as_ref().map(|d| match d {
+        Descriptor::File(path) => quote! {
+            info.descriptor = ::std::include_bytes!(::std::concat!(::std::env!("CARGO_MANIFEST_DIR"), "/", #path)).to_vec();
+        },
+        Descriptor::Function(path) => quote! {
+            info.descriptor = #path();
+        },
+        Descriptor::Schema => quote! {
+            info.descriptor = ::rustecal_types_serde::schema::schema_descriptor::<Self>();
+        },
+    });
+
+    Ok(quote! {
+        #extra
+
+        impl #impl_generics #krate::PublisherMessage for #ident #ty_generics #where_clause {
+            fn datatype() -> #krate::DataTypeInfo {
+                #[allow(unused_mut)]
+                let mut info = #base_datatype;
+                #encoding
+                #type_name
+                #descriptor
+                info
+            }
+
+            fn to_bytes(
+                &self,
+            ) -> ::std::result::Result<::std::sync::Arc<[u8]>, #krate::SerializationError> {
+                #encode
+            }
+        }
+
+        impl #sub_impl_generics #krate::SubscriberMessage<'__ecal_buf> for #ident #ty_generics #where_clause {
+            fn datatype() -> #krate::DataTypeInfo {
+                <Self as #krate::PublisherMessage>::datatype()
+            }
+
+            fn from_bytes(
+                bytes: &'__ecal_buf [u8],
+                _data_type_info: &#krate::DataTypeInfo,
+            ) -> ::std::option::Option<Self> {
+                #decode
+            }
+        }
+    })
+}
@@ -0,0 +1,99 @@
+use rustecal_derive::EcalMessage;
+use rustecal_pubsub::{PublisherMessage, SubscriberMessage};
+use serde::{Deserialize, Serialize};
+
+mod drive {
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, EcalMessage)]
+    pub struct Status {
+        pub speed: f32,
+    }
+}
+
+mod battery {
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, EcalMessage)]
+    #[ecal(
+        format = "cbor",
+        type_name = "robot.battery.Status",
+        encoding = "cbor-v2"
+    )]
+    pub struct Status {
+        pub level: u8,
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, schemars::JsonSchema, EcalMessage)]
+#[ecal(format = "msgpack", schema)]
+struct WithSchema {
+    name: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message, prost_reflect::ReflectMessage, EcalMessage)]
+#[prost_reflect(
+    descriptor_pool = "test_rustecal_types_protobuf::DESCRIPTOR_POOL",
+    message_name = "example.msg.basic.Basic"
+)]
+#[ecal(format = "protobuf")]
+struct Basic {
+    #[prost(string, tag = "1")]
+    name: String,
+    #[prost(uint32, tag = "2")]
+    count: u32,
+    #[prost(float, tag = "3")]
+    value: f32,
+}
+
+#[test]
+fn default_type_name_is_module_qualified() {
+    let drive = <drive::Status as PublisherMessage>::datatype();
+    assert_eq!(drive.encoding, "json");
+    assert_eq!(drive.type_name, "derive::drive::Status");
+    assert!(drive.descriptor.is_empty());
+}
+
+#[test]
+fn attributes_override_defaults() {
+    let battery = <battery::Status as PublisherMessage>::datatype();
+    assert_eq!(battery.encoding, "cbor-v2");
+    assert_eq!(battery.type_name, "robot.battery.Status");
+}
+
+#[test]
+fn serde_round_trip() {
+    let status = battery::Status { level: 42 };
+    let bytes = status.to_bytes().unwrap();
+    let info = <battery::Status as SubscriberMessage>::datatype();
+    assert_eq!(
+        <battery::Status as SubscriberMessage>::from_bytes(&bytes, &info),
+        Some(status)
+    );
+}
+
+#[test]
+fn schema_descriptor() {
+    let info = <WithSchema as PublisherMessage>::datatype();
+    let schema: serde_json::Value = serde_json::from_slice(&info.descriptor).unwrap();
+    assert_eq!(schema["title"], "WithSchema");
+}
+
+#[test]
+fn protobuf_message() {
+    let info = <Basic as PublisherMessage>::datatype();
+    assert_eq!(info.encoding, "proto");
+    assert_eq!(info.type_name, "example.msg.basic.Basic");
+    assert!(!info.descriptor.is_empty());
+
+    let basic = Basic {
+        name: "basic".into(),
+        count: 3,
+        value: 1.5,
+    };
+    let bytes = basic.to_bytes().unwrap();
+    assert_eq!(
+        <Basic as SubscriberMessage>::from_bytes(&bytes, &info),
+        Some(basic)
+    );
+}
@@ -0,0 +1,58 @@
+//! String attributes as written by the eCAL C++ HDF5 library.
+//!
+//! eCAL stores all attributes, including binary descriptors, as scalar
+//! fixed-length strings sized to the value. A zero-sized string type is not
+//! allowed, so empty values are written as a single NUL byte.
+
+use hdf5::Location;
+use hdf5::types::{TypeDescriptor, VarLenAscii, VarLenUnicode};
+use hdf5_sys::h5a::{H5Aread, H5Awrite};
+
+/// Reads a string attribute as raw bytes, `None` if it does not exist.
+pub(crate) fn read(location: &Location, name: &str) -> hdf5::Result<Option<Vec<u8>>> {
+    if !location.attr_names()?.iter().any(|attr| attr == name) {
+        return Ok(None);
+    }
+    let attr = location.attr(name)?;
+    let dtype = attr.dtype()?;
+    let value = match dtype.to_descriptor()? {
+        TypeDescriptor::VarLenAscii => attr.read_scalar::<VarLenAscii>()?.as_bytes().to_vec(),
+        TypeDescriptor::VarLenUnicode => attr.read_scalar::<VarLenUnicode>()?.as_bytes().to_vec(),
+        _ => {
+            let mut buf = vec![0u8; dtype.size()];
+            hdf5::sync::sync(|| {
+                // SAFETY: `buf` holds exactly one element of the attribute's own type
+                hdf5::h5check(unsafe { H5Aread(attr.id(), dtype.id(), buf.as_mut_ptr().cast()) })
+            })?;
+            if buf == [0] { Vec::new() } else { buf }
+        }
+    };
+    Ok(Some(value))
+}
+
+/// Reads a string attribute as text, replacing invalid UTF-8 and dropping NUL padding.
+pub(crate) fn read_string(location: &Location, name: &str) -> hdf5::Result<Option<String>> {
+    Ok(read(location, name)?.map(|bytes| {
+        String::from_utf8_lossy(&bytes)
+            .trim_end_matches('\0')
+            .to_string()
+    }))
+}
+
+/// Writes a string attribute, replacing an existing one.
+pub(crate) fn write(location: &Location, name: &str, value: &[u8]) -> hdf5::Result<()> {
+    if location.attr_names()?.iter().any(|attr| attr == name) {
+        location.delete_attr(name)?;
+    }
+    let value = if value.is_empty() { &[0][..] } else { value };
+    let attr = location
+        .new_attr_builder()
+        .empty_as(&TypeDescriptor::FixedAscii(value.len()))
+        .create(name)?;
+    let dtype = attr.dtype()?;
+    hdf5::sync::sync(|| {
+        // SAFETY: `value` holds exactly one element of the attribute's type
+        hdf5::h5check(unsafe { H5Awrite(attr.id(), dtype.id(), value.as_ptr().cast()) })
+    })?;
+    Ok(())
+}
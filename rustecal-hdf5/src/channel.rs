@@ -0,0 +1,38 @@
+//! Channels and entries of a measurement.
+
+/// A recorded channel: a topic as published by one publisher.
+///
+/// eCAL measurements from format version 6 on keep the messages of different
+/// publishers on the same topic apart by `id`; older versions use `0`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Channel {
+    pub name: String,
+    pub id: u64,
+}
+
+impl Channel {
+    pub fn new(name: impl Into<String>, id: u64) -> Self {
+        Self {
+            name: name.into(),
+            id,
+        }
+    }
+}
+
+/// Timestamps and clock of a recorded message; the payload is read with
+/// [`MeasurementReader::read`](crate::MeasurementReader::read).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry {
+    /// eCAL time at receive in microseconds.
+    pub receive_timestamp: i64,
+    /// Publisher's send timestamp in microseconds.
+    pub send_timestamp: i64,
+    /// Publisher's send clock.
+    pub send_clock: i64,
+    /// Publisher's send id.
+    pub send_id: i64,
+    /// Index of the file holding the payload.
+    pub(crate) file: usize,
+    /// Name of the payload dataset within the file.
+    pub(crate) dataset: i64,
+}
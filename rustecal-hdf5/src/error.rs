@@ -0,0 +1,28 @@
+//! Error types of the HDF5 measurement reader and writer.
+
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Errors returned while reading or writing a measurement.
+#[derive(Debug, Error)]
+pub enum Hdf5Error {
+    /// The HDF5 library reported an error.
+    #[error("HDF5 error: {0}")]
+    Hdf5(#[from] hdf5::Error),
+
+    /// Listing or creating the measurement directory failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The path holds no `.hdf5` files.
+    #[error("no HDF5 files found in '{0}'")]
+    NoFiles(PathBuf),
+
+    /// The file was written in a format version this crate cannot read.
+    #[error("unsupported measurement format version '{version}' in '{path}'")]
+    UnsupportedVersion { path: PathBuf, version: String },
+
+    /// The file does not follow the eCAL measurement layout.
+    #[error("invalid measurement file '{path}': {reason}")]
+    Invalid { path: PathBuf, reason: String },
+}
@@ -0,0 +1,33 @@
+//! Names used by the eCAL HDF5 measurement format, see the crate documentation.
+
+pub(crate) const FILE_EXTENSION: &str = "hdf5";
+
+pub(crate) const VERSION_ATTR: &str = "Version";
+pub(crate) const VERSION: &str = "6.0";
+
+pub(crate) const TYPE_NAME_ATTR: &str = "TypeName";
+pub(crate) const TYPE_ENCODING_ATTR: &str = "TypeEncoding";
+pub(crate) const TYPE_DESCRIPTOR_ATTR: &str = "TypeDescriptor";
+pub(crate) const DATA_TABLE: &str = "DataTable";
+
+pub(crate) const V5_CHANNELS_ATTR: &str = "Channels";
+pub(crate) const V5_CHANNEL_TYPE_ATTR: &str = "Channel Type";
+pub(crate) const V5_CHANNEL_DESCRIPTION_ATTR: &str = "Channel Description";
+
+/// Columns of an entry table row.
+pub(crate) const ENTRY_COLUMNS: usize = 5;
+
+/// Formats a channel id as the name of its group.
+pub(crate) fn channel_group_name(id: u64) -> String {
+    format!("{id:016x}")
+}
+
+/// Escapes a topic name for use as a group name, as `/` separates HDF5 paths.
+pub(crate) fn escape_topic(name: &str) -> String {
+    name.replace('%', "%25").replace('/', "%2F")
+}
+
+/// Reverses [`escape_topic`].
+pub(crate) fn unescape_topic(name: &str) -> String {
+    name.replace("%2F", "/").replace("%25", "%")
+}
@@ -0,0 +1,49 @@
+//! # rustecal-hdf5
+//!
+//! Reads and writes eCAL HDF5 measurements, the format of the eCAL recorder,
+//! so existing recordings can be processed offline.
+//!
+//! A measurement is a directory with one or more `.hdf5` files (e.g. one per
+//! recording host, split by size). [`MeasurementReader`] opens all of them and
+//! lists the recorded [`Channel`]s with their `DataTypeInfo` and [`Entry`]s;
+//! [`MeasurementWriter`] writes the same format.
+//!
+//! Requires the HDF5 C library (1.10 or later) at build time, located via
+//! `HDF5_DIR` or `pkg-config`.
+//!
+//! ## Layout
+//!
+//! Format version 6, written by this crate:
+//!
+//! ```text
+//! /                              attribute "Version" = "6.0"
+//! ├── 0, 1, 2, ...               one u8 dataset per message payload
+//! └── <topic name>/
+//!     └── <channel id, hex>/     attributes "TypeName", "TypeEncoding", "TypeDescriptor"
+//!         └── DataTable          i64 [entries x 5]
+//! ```
+//!
+//! Format version 5, read only:
+//!
+//! ```text
+//! /                              attributes "Version" = "5.x", "Channels" = "<topic>,<topic>,..."
+//! ├── 0, 1, 2, ...               one u8 dataset per message payload
+//! └── <topic name>               i64 [entries x 5], attributes "Channel Type" = "<encoding>:<type name>",
+//!                                "Channel Description" = descriptor
+//! ```
+//!
+//! Every row of an entry table holds receive timestamp, payload dataset id,
+//! send clock, send timestamp and send id, timestamps in microseconds. Topic
+//! names are stored with `/` escaped as `%2F` (and `%` as `%25`).
+
+mod attribute;
+pub mod channel;
+pub mod error;
+mod format;
+pub mod reader;
+pub mod writer;
+
+pub use channel::{Channel, Entry};
+pub use error::Hdf5Error;
+pub use reader::MeasurementReader;
+pub use writer::{MeasurementWriter, WriteEntry};
@@ -0,0 +1,248 @@
+//! Reading eCAL HDF5 measurements.
+
+use crate::attribute;
+use crate::channel::{Channel, Entry};
+use crate::error::Hdf5Error;
+use crate::format::*;
+use hdf5::{Dataset, File, LocationType};
+use rustecal_core::types::DataTypeInfo;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// A channel with its data type and entries from all files.
+struct ChannelData {
+    data_type: DataTypeInfo,
+    /// Sorted by receive timestamp.
+    entries: Vec<Entry>,
+}
+
+/// Reads an eCAL HDF5 measurement, as written by the eCAL recorder or
+/// [`MeasurementWriter`](crate::MeasurementWriter).
+///
+/// ```ignore
+/// let measurement = MeasurementReader::open("2024-05-13_10-04-12.345_measurement")?;
+/// for channel in measurement.channels() {
+///     let data_type = measurement.data_type(channel).unwrap();
+///     println!("{} ({}:{})", channel.name, data_type.encoding, data_type.type_name);
+///     for entry in measurement.entries(channel) {
+///         let payload = measurement.read(entry)?;
+///     }
+/// }
+/// ```
+///
+/// Format versions 5 and 6 are supported. Channel tables are loaded when
+/// opening; payloads are read on demand.
+pub struct MeasurementReader {
+    files: Vec<(PathBuf, File)>,
+    channels: BTreeMap<Channel, ChannelData>,
+}
+
+impl MeasurementReader {
+    /// Opens a single `.hdf5` file, or all `.hdf5` files below a measurement
+    /// directory (e.g. one per recording host, and the parts of split files).
+    ///
+    /// # Errors
+    /// Returns a [`Hdf5Error`] if no file is found or a file cannot be read.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Hdf5Error> {
+        let path = path.as_ref();
+        let mut paths = Vec::new();
+        if path.is_dir() {
+            find_files(path, &mut paths)?;
+            paths.sort();
+        } else {
+            paths.push(path.to_path_buf());
+        }
+        if paths.is_empty() {
+            return Err(Hdf5Error::NoFiles(path.to_path_buf()));
+        }
+
+        let mut reader = Self {
+            files: Vec::new(),
+            channels: BTreeMap::new(),
+        };
+        for path in paths {
+            reader.add_file(path)?;
+        }
+        for channel in reader.channels.values_mut() {
+            channel.entries.sort_by_key(|entry| entry.receive_timestamp);
+        }
+        Ok(reader)
+    }
+
+    /// Returns the opened files.
+    pub fn files(&self) -> Vec<&Path> {
+        self.files.iter().map(|(path, _)| path.as_path()).collect()
+    }
+
+    /// Returns all channels, sorted by name and id.
+    pub fn channels(&self) -> Vec<&Channel> {
+        self.channels.keys().collect()
+    }
+
+    /// Returns the data type of a channel.
+    pub fn data_type(&self, channel: &Channel) -> Option<&DataTypeInfo> {
+        self.channels.get(channel).map(|data| &data.data_type)
+    }
+
+    /// Returns the entries of a channel, sorted by receive timestamp.
+    pub fn entries(&self, channel: &Channel) -> &[Entry] {
+        self.channels
+            .get(channel)
+            .map(|data| data.entries.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Returns the entries of all channels, sorted by receive timestamp.
+    pub fn timeline(&self) -> Vec<(&Channel, &Entry)> {
+        let mut timeline: Vec<(&Channel, &Entry)> = self
+            .channels
+            .iter()
+            .flat_map(|(channel, data)| data.entries.iter().map(move |entry| (channel, entry)))
+            .collect();
+        timeline.sort_by_key(|(_, entry)| entry.receive_timestamp);
+        timeline
+    }
+
+    /// Receive timestamp of the first entry in microseconds.
+    pub fn start_time(&self) -> Option<i64> {
+        self.channels
+            .values()
+            .filter_map(|data| data.entries.first())
+            .map(|entry| entry.receive_timestamp)
+            .min()
+    }
+
+    /// Receive timestamp of the last entry in microseconds.
+    pub fn end_time(&self) -> Option<i64> {
+        self.channels
+            .values()
+            .filter_map(|data| data.entries.last())
+            .map(|entry| entry.receive_timestamp)
+            .max()
+    }
+
+    /// Reads the payload of an entry.
+    ///
+    /// # Errors
+    /// Returns a [`Hdf5Error`] if the payload dataset cannot be read.
+    pub fn read(&self, entry: &Entry) -> Result<Vec<u8>, Hdf5Error> {
+        let (_, file) = &self.files[entry.file];
+        Ok(file.dataset(&entry.dataset.to_string())?.read_raw::<u8>()?)
+    }
+
+    fn add_file(&mut self, path: PathBuf) -> Result<(), Hdf5Error> {
+        let file = File::open(&path)?;
+        let index = self.files.len();
+        let version =
+            attribute::read_string(&file, VERSION_ATTR)?.ok_or_else(|| Hdf5Error::Invalid {
+                path: path.clone(),
+                reason: format!("missing '{VERSION_ATTR}' attribute"),
+            })?;
+
+        let major = version.split('.').next().unwrap_or_default();
+        match major {
+            "6" => self.read_v6(&path, &file, index)?,
+            "5" => self.read_v5(&path, &file, index)?,
+            _ => return Err(Hdf5Error::UnsupportedVersion { path, version }),
+        }
+        self.files.push((path, file));
+        Ok(())
+    }
+
+    fn read_v6(&mut self, path: &Path, file: &File, index: usize) -> Result<(), Hdf5Error> {
+        for name in file.member_names()? {
+            if file.loc_type_by_name(&name)? != LocationType::Group {
+                continue;
+            }
+            let topic = file.group(&name)?;
+            for id_name in topic.member_names()? {
+                let Ok(id) = u64::from_str_radix(&id_name, 16) else {
+                    continue;
+                };
+                let group = topic.group(&id_name)?;
+                let data_type = DataTypeInfo {
+                    type_name: attribute::read_string(&group, TYPE_NAME_ATTR)?.unwrap_or_default(),
+                    encoding: attribute::read_string(&group, TYPE_ENCODING_ATTR)?
+                        .unwrap_or_default(),
+                    descriptor: attribute::read(&group, TYPE_DESCRIPTOR_ATTR)?.unwrap_or_default(),
+                };
+                let entries = read_entries(path, &group.dataset(DATA_TABLE)?, index)?;
+                self.add_channel(Channel::new(unescape_topic(&name), id), data_type, entries);
+            }
+        }
+        Ok(())
+    }
+
+    fn read_v5(&mut self, path: &Path, file: &File, index: usize) -> Result<(), Hdf5Error> {
+        let names = attribute::read_string(file, V5_CHANNELS_ATTR)?.unwrap_or_default();
+        for name in names.split(',').filter(|name| !name.is_empty()) {
+            let dataset = file.dataset(name)?;
+            let channel_type =
+                attribute::read_string(&dataset, V5_CHANNEL_TYPE_ATTR)?.unwrap_or_default();
+            // "proto:pb.People.Person"; types without encoding have no prefix
+            let (encoding, type_name) = channel_type
+                .split_once(':')
+                .unwrap_or(("", channel_type.as_str()));
+            let data_type = DataTypeInfo {
+                type_name: type_name.to_string(),
+                encoding: encoding.to_string(),
+                descriptor: attribute::read(&dataset, V5_CHANNEL_DESCRIPTION_ATTR)?
+                    .unwrap_or_default(),
+            };
+            let entries = read_entries(path, &dataset, index)?;
+            self.add_channel(Channel::new(name, 0), data_type, entries);
+        }
+        Ok(())
+    }
+
+    fn add_channel(&mut self, channel: Channel, data_type: DataTypeInfo, entries: Vec<Entry>) {
+        self.channels
+            .entry(channel)
+            .or_insert_with(|| ChannelData {
+                data_type,
+                entries: Vec::new(),
+            })
+            .entries
+            .extend(entries);
+    }
+}
+
+/// Reads an entry table; tables with four columns lack the send id.
+fn read_entries(path: &Path, table: &Dataset, file: usize) -> Result<Vec<Entry>, Hdf5Error> {
+    let shape = table.shape();
+    let columns = match shape.as_slice() {
+        [_, columns] if *columns >= ENTRY_COLUMNS - 1 => *columns,
+        _ => {
+            return Err(Hdf5Error::Invalid {
+                path: path.to_path_buf(),
+                reason: format!("unexpected entry table shape {shape:?}"),
+            });
+        }
+    };
+
+    let values = table.read_raw::<i64>()?;
+    Ok(values
+        .chunks_exact(columns)
+        .map(|row| Entry {
+            receive_timestamp: row[0],
+            dataset: row[1],
+            send_clock: row[2],
+            send_timestamp: row[3],
+            send_id: row.get(4).copied().unwrap_or_default(),
+            file,
+        })
+        .collect())
+}
+
+/// Collects all `.hdf5` files below `dir`.
+fn find_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            find_files(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == FILE_EXTENSION) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
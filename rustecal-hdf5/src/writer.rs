@@ -0,0 +1,188 @@
+//! Writing eCAL HDF5 measurements.
+
+use crate::attribute;
+use crate::channel::Channel;
+use crate::error::Hdf5Error;
+use crate::format::*;
+use hdf5::{File, Group};
+use rustecal_core::types::DataTypeInfo;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// A message to be written.
+#[derive(Debug, Clone, Copy)]
+pub struct WriteEntry<'a> {
+    pub channel: &'a Channel,
+    /// Data type of the channel; only the first one written per channel and file is kept.
+    pub data_type: &'a DataTypeInfo,
+    /// eCAL time at receive in microseconds.
+    pub receive_timestamp: i64,
+    /// Publisher's send timestamp in microseconds.
+    pub send_timestamp: i64,
+    pub send_clock: i64,
+    pub send_id: i64,
+    pub data: &'a [u8],
+}
+
+/// Writes an eCAL HDF5 measurement (format version 6) that the eCAL
+/// measurement tools can open.
+///
+/// ```ignore
+/// let mut writer = MeasurementWriter::create("measurement/host", "host")?
+///     .with_max_file_size(512 << 20);
+/// writer.write(&WriteEntry { channel: &channel, data_type: &data_type, .. })?;
+/// let files = writer.finish()?;
+/// ```
+///
+/// Files are named `<base_name>.hdf5`, `<base_name>_1.hdf5`, ... in the
+/// output directory. Channel tables are written when a file is closed.
+pub struct MeasurementWriter {
+    dir: PathBuf,
+    base_name: String,
+    max_file_size: Option<u64>,
+    current: Option<WriterFile>,
+    files: Vec<PathBuf>,
+}
+
+/// An open output file.
+struct WriterFile {
+    file: File,
+    next_dataset: i64,
+    /// Data type and flattened entry table per channel.
+    channels: BTreeMap<Channel, (DataTypeInfo, Vec<i64>)>,
+}
+
+impl MeasurementWriter {
+    /// Creates the output directory and the first file.
+    ///
+    /// # Errors
+    /// Returns a [`Hdf5Error`] if the directory or file cannot be created.
+    pub fn create(
+        dir: impl Into<PathBuf>,
+        base_name: impl Into<String>,
+    ) -> Result<Self, Hdf5Error> {
+        let mut writer = Self {
+            dir: dir.into(),
+            base_name: base_name.into(),
+            max_file_size: None,
+            current: None,
+            files: Vec::new(),
+        };
+        std::fs::create_dir_all(&writer.dir)?;
+        writer.open_next()?;
+        Ok(writer)
+    }
+
+    /// Starts a new file once the current one reaches `bytes`.
+    pub fn with_max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = Some(bytes);
+        self
+    }
+
+    /// Appends a message, starting a new file first if the size limit is reached.
+    ///
+    /// # Errors
+    /// Returns a [`Hdf5Error`] if writing or rolling over fails.
+    pub fn write(&mut self, entry: &WriteEntry<'_>) -> Result<(), Hdf5Error> {
+        if self.is_full() {
+            self.close_current()?;
+        }
+        let file = match &mut self.current {
+            Some(file) => file,
+            None => self.open_next()?,
+        };
+
+        let dataset = file.next_dataset;
+        file.file
+            .new_dataset::<u8>()
+            .shape(entry.data.len())
+            .create(dataset.to_string().as_str())?
+            .write_raw(entry.data)?;
+        file.next_dataset += 1;
+
+        let (_, table) = file
+            .channels
+            .entry(entry.channel.clone())
+            .or_insert_with(|| (entry.data_type.clone(), Vec::new()));
+        table.extend([
+            entry.receive_timestamp,
+            dataset,
+            entry.send_clock,
+            entry.send_timestamp,
+            entry.send_id,
+        ]);
+        Ok(())
+    }
+
+    /// Returns the files written so far, including the open one.
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// Writes the channel tables of the open file and returns all written files.
+    ///
+    /// # Errors
+    /// Returns a [`Hdf5Error`] if the channel tables cannot be written.
+    pub fn finish(mut self) -> Result<Vec<PathBuf>, Hdf5Error> {
+        self.close_current()?;
+        Ok(std::mem::take(&mut self.files))
+    }
+
+    fn is_full(&self) -> bool {
+        match (&self.current, self.max_file_size) {
+            // every file holds at least one message
+            (Some(file), Some(max)) => file.next_dataset > 0 && file.file.size() >= max,
+            _ => false,
+        }
+    }
+
+    fn open_next(&mut self) -> Result<&mut WriterFile, Hdf5Error> {
+        let name = match self.files.len() {
+            0 => format!("{}.{FILE_EXTENSION}", self.base_name),
+            n => format!("{}_{n}.{FILE_EXTENSION}", self.base_name),
+        };
+        let path = self.dir.join(name);
+        let file = File::create(&path)?;
+        attribute::write(&file, VERSION_ATTR, VERSION.as_bytes())?;
+        self.files.push(path);
+        Ok(self.current.insert(WriterFile {
+            file,
+            next_dataset: 0,
+            channels: BTreeMap::new(),
+        }))
+    }
+
+    fn close_current(&mut self) -> Result<(), Hdf5Error> {
+        let Some(current) = self.current.take() else {
+            return Ok(());
+        };
+        for (channel, (data_type, table)) in &current.channels {
+            let topic = open_or_create_group(&current.file, &escape_topic(&channel.name))?;
+            let group = open_or_create_group(&topic, &channel_group_name(channel.id))?;
+            attribute::write(&group, TYPE_NAME_ATTR, data_type.type_name.as_bytes())?;
+            attribute::write(&group, TYPE_ENCODING_ATTR, data_type.encoding.as_bytes())?;
+            attribute::write(&group, TYPE_DESCRIPTOR_ATTR, &data_type.descriptor)?;
+            group
+                .new_dataset::<i64>()
+                .shape((table.len() / ENTRY_COLUMNS, ENTRY_COLUMNS))
+                .create(DATA_TABLE)?
+                .write_raw(table)?;
+        }
+        current.file.close()?;
+        Ok(())
+    }
+}
+
+impl Drop for MeasurementWriter {
+    fn drop(&mut self) {
+        let _ = self.close_current();
+    }
+}
+
+fn open_or_create_group(parent: &Group, name: &str) -> hdf5::Result<Group> {
+    if parent.link_exists(name) {
+        parent.group(name)
+    } else {
+        parent.create_group(name)
+    }
+}
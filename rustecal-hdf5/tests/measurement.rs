@@ -0,0 +1,181 @@
+use hdf5::types::VarLenUnicode;
+use rustecal_core::types::DataTypeInfo;
+use rustecal_hdf5::{Channel, Hdf5Error, MeasurementReader, MeasurementWriter, WriteEntry};
+use std::path::PathBuf;
+
+/// Returns an empty directory for the output of one test.
+fn output_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rustecal-hdf5-{}-{test}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn proto_type() -> DataTypeInfo {
+    DataTypeInfo {
+        encoding: "proto".to_string(),
+        type_name: "pb.People.Person".to_string(),
+        // binary descriptors may contain NUL bytes, also at the end
+        descriptor: vec![10, 0, 3, 0],
+    }
+}
+
+fn string_type() -> DataTypeInfo {
+    DataTypeInfo {
+        encoding: "utf-8".to_string(),
+        type_name: "std::string".to_string(),
+        descriptor: Vec::new(),
+    }
+}
+
+/// Writes `count` messages, cycling over the given channels, one every 10 ms.
+fn write(writer: &mut MeasurementWriter, channels: &[(Channel, DataTypeInfo)], count: i64) {
+    for i in 0..count {
+        let (channel, data_type) = &channels[i as usize % channels.len()];
+        let data = vec![i as u8; (i % 4) as usize];
+        writer
+            .write(&WriteEntry {
+                channel,
+                data_type,
+                receive_timestamp: 1_000_000 + i * 10_000 + 5,
+                send_timestamp: 1_000_000 + i * 10_000,
+                send_clock: i,
+                send_id: 7,
+                data: &data,
+            })
+            .unwrap();
+    }
+}
+
+#[test]
+fn round_trips_channels_entries_and_payloads() {
+    let dir = output_dir("round-trip");
+    let channels = [
+        (Channel::new("person", 0x1234), proto_type()),
+        (Channel::new("person", 0x5678), proto_type()),
+        (Channel::new("/ns/hello", 1), string_type()),
+    ];
+    let mut writer = MeasurementWriter::create(dir.join("host"), "host").unwrap();
+    write(&mut writer, &channels, 9);
+    let files = writer.finish().unwrap();
+    assert_eq!(files, vec![dir.join("host").join("host.hdf5")]);
+
+    let reader = MeasurementReader::open(&dir).unwrap();
+    let found: Vec<&Channel> = reader.channels();
+    assert_eq!(
+        found,
+        vec![&channels[2].0, &channels[0].0, &channels[1].0],
+        "channels are sorted by name and id"
+    );
+
+    let person = reader.data_type(&channels[0].0).unwrap();
+    assert_eq!(person.encoding, "proto");
+    assert_eq!(person.type_name, "pb.People.Person");
+    assert_eq!(person.descriptor, vec![10, 0, 3, 0]);
+    let hello = reader.data_type(&channels[2].0).unwrap();
+    assert_eq!(hello.type_name, "std::string");
+    assert!(hello.descriptor.is_empty());
+
+    let entries = reader.entries(&channels[1].0);
+    let clocks: Vec<i64> = entries.iter().map(|entry| entry.send_clock).collect();
+    assert_eq!(clocks, vec![1, 4, 7]);
+    assert_eq!(entries[1].send_timestamp, 1_040_000);
+    assert_eq!(entries[1].receive_timestamp, 1_040_005);
+    assert_eq!(entries[1].send_id, 7);
+    assert_eq!(reader.read(&entries[0]).unwrap(), vec![1]);
+    assert_eq!(reader.read(&entries[1]).unwrap(), Vec::<u8>::new());
+    assert_eq!(reader.read(&entries[2]).unwrap(), vec![7, 7, 7]);
+
+    assert_eq!(reader.start_time(), Some(1_000_005));
+    assert_eq!(reader.end_time(), Some(1_080_005));
+}
+
+#[test]
+fn reads_split_files_as_one_measurement() {
+    let dir = output_dir("split");
+    let channels = [(Channel::new("hello", 0), string_type())];
+    let mut writer = MeasurementWriter::create(&dir, "measurement")
+        .unwrap()
+        .with_max_file_size(1);
+    write(&mut writer, &channels, 3);
+    let files = writer.finish().unwrap();
+    assert_eq!(
+        files,
+        vec![
+            dir.join("measurement.hdf5"),
+            dir.join("measurement_1.hdf5"),
+            dir.join("measurement_2.hdf5"),
+        ]
+    );
+
+    let reader = MeasurementReader::open(&dir).unwrap();
+    assert_eq!(reader.files().len(), 3);
+    let timeline = reader.timeline();
+    let clocks: Vec<i64> = timeline.iter().map(|(_, entry)| entry.send_clock).collect();
+    assert_eq!(clocks, vec![0, 1, 2]);
+    let payloads: Vec<Vec<u8>> = timeline
+        .iter()
+        .map(|(_, entry)| reader.read(entry).unwrap())
+        .collect();
+    assert_eq!(payloads, vec![vec![], vec![1], vec![2, 2]]);
+}
+
+#[test]
+fn reads_version_5_files() {
+    let path = output_dir("v5").join("measurement.hdf5");
+    {
+        let file = hdf5::File::create(&path).unwrap();
+        let attr = |location: &hdf5::Location, name: &str, value: &str| {
+            location
+                .new_attr::<VarLenUnicode>()
+                .create(name)
+                .unwrap()
+                .write_scalar(&value.parse::<VarLenUnicode>().unwrap())
+                .unwrap();
+        };
+        attr(&file, "Version", "5.0");
+        attr(&file, "Channels", "hello");
+        for (id, data) in [b"hi".as_slice(), b"there"].into_iter().enumerate() {
+            file.new_dataset::<u8>()
+                .shape(data.len())
+                .create(id.to_string().as_str())
+                .unwrap()
+                .write_raw(data)
+                .unwrap();
+        }
+        // four columns, without send id
+        let table = file
+            .new_dataset::<i64>()
+            .shape((2, 4))
+            .create("hello")
+            .unwrap();
+        table.write_raw(&[200, 1, 2, 190, 100, 0, 1, 90]).unwrap();
+        attr(&table, "Channel Type", "utf-8:std::string");
+        attr(&table, "Channel Description", "");
+    }
+
+    let reader = MeasurementReader::open(&path).unwrap();
+    let channel = Channel::new("hello", 0);
+    assert_eq!(reader.channels(), vec![&channel]);
+    let data_type = reader.data_type(&channel).unwrap();
+    assert_eq!(data_type.encoding, "utf-8");
+    assert_eq!(data_type.type_name, "std::string");
+
+    let entries = reader.entries(&channel);
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].receive_timestamp, 100);
+    assert_eq!(entries[0].send_timestamp, 90);
+    assert_eq!(entries[0].send_clock, 1);
+    assert_eq!(entries[0].send_id, 0);
+    assert_eq!(reader.read(&entries[0]).unwrap(), b"hi");
+    assert_eq!(reader.read(&entries[1]).unwrap(), b"there");
+}
+
+#[test]
+fn rejects_directories_without_measurement_files() {
+    let dir = output_dir("empty");
+    assert!(matches!(
+        MeasurementReader::open(&dir),
+        Err(Hdf5Error::NoFiles(_))
+    ));
+}
@@ -0,0 +1,23 @@
+//! Error types of the player.
+
+use thiserror::Error;
+
+/// Errors returned while loading or playing a measurement.
+#[derive(Debug, Error)]
+pub enum PlayError {
+    /// Opening or mapping a measurement file failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A measurement file is not valid MCAP.
+    #[error("MCAP error: {0}")]
+    Mcap(#[from] mcap::McapError),
+
+    /// The playback rate is not a positive number.
+    #[error("invalid playback rate {0}, must be positive")]
+    InvalidRate(f64),
+
+    /// A publisher for the topic could not be created.
+    #[error("failed to create publisher for '{topic}': {reason}")]
+    Publish { topic: String, reason: String },
+}
@@ -0,0 +1,18 @@
+//! # rustecal-play
+//!
+//! Replays [MCAP](https://mcap.dev) recordings into eCAL, e.g. those written by `rustecal-record`.
+//!
+//! Every recorded topic is published with its original data type (restored
+//! from the channel metadata and schema), and messages are sent with their
+//! original inter-message timing, optionally scaled, looped, limited to a
+//! time range or published on remapped topic names.
+//!
+//! The crate also builds the `rustecal-play` command line player.
+
+pub mod error;
+pub mod measurement;
+pub mod player;
+
+pub use error::PlayError;
+pub use measurement::{ChannelInfo, Measurement, MeasurementMessage};
+pub use player::{PlayOptions, PlayStats, Player, PlayerHandle};
@@ -0,0 +1,147 @@
+//! Command line player replaying MCAP recordings into eCAL.
+
+use rustecal_core::{Ecal, EcalComponents};
+use rustecal_play::{Measurement, PlayOptions, Player};
+use rustecal_tools_common::cli::parse_duration;
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+
+const USAGE: &str = "\
+Usage: rustecal-play [OPTIONS] <FILE>...
+
+Publishes the messages of one or more MCAP files (e.g. the parts of a split
+recording) into eCAL with their original timing.
+
+Options:
+  -r, --rate <FACTOR>     Playback speed factor, or 'max' for no delays (default: 1)
+  -l, --loop              Restart at the beginning after the last message
+      --start <TIME>      Start at TIME into the measurement (suffixes ms, s, m, h)
+      --end <TIME>        Stop at TIME into the measurement
+  -t, --topic <NAME>      Topic to play, may be repeated (default: all topics)
+  -m, --remap <FROM=TO>   Publish the recorded topic FROM as TO, may be repeated
+      --delay <TIME>      Wait before playing so subscribers can connect (default: 2s)
+  -h, --help              Print this help";
+
+struct Args {
+    files: Vec<String>,
+    options: PlayOptions,
+    delay: Duration,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut files = Vec::new();
+    let mut options = PlayOptions::default();
+    let mut topics = Vec::new();
+    let mut delay = Duration::from_secs(2);
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value for {arg}"))
+        };
+        match arg.as_str() {
+            "-r" | "--rate" => options.rate = parse_rate(&value()?)?,
+            "-l" | "--loop" => options.looping = true,
+            "--start" => options.start = Some(parse_duration(&value()?)?),
+            "--end" => options.end = Some(parse_duration(&value()?)?),
+            "-t" | "--topic" => topics.push(value()?),
+            "-m" | "--remap" => {
+                let remap = value()?;
+                let (from, to) = remap
+                    .split_once('=')
+                    .ok_or_else(|| format!("invalid remapping '{remap}', expected FROM=TO"))?;
+                options = options.with_remap(from, to);
+            }
+            "--delay" => delay = parse_duration(&value()?)?,
+            "-h" | "--help" => return Ok(None),
+            other if other.starts_with('-') => {
+                return Err(format!("unexpected argument '{other}'"));
+            }
+            _ => files.push(arg),
+        }
+    }
+
+    if files.is_empty() {
+        return Err("no input file given".to_string());
+    }
+    if !topics.is_empty() {
+        options = options.with_topics(topics);
+    }
+    Ok(Some(Args {
+        files,
+        options,
+        delay,
+    }))
+}
+
+/// Parses a speed factor like `0.5`, or `max`.
+fn parse_rate(text: &str) -> Result<f64, String> {
+    if text == "max" {
+        return Ok(f64::INFINITY);
+    }
+    match text.parse::<f64>() {
+        Ok(rate) if rate > 0.0 => Ok(rate),
+        _ => Err(format!("invalid rate '{text}'")),
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(error) => {
+            eprintln!("error: {error}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if let Err(error) = Ecal::initialize(Some("rustecal play"), EcalComponents::DEFAULT, None) {
+        eprintln!("error: eCAL initialization failed: {error}");
+        return ExitCode::FAILURE;
+    }
+
+    let result = play(args);
+    Ecal::finalize();
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn play(args: Args) -> Result<(), Box<dyn std::error::Error>> {
+    let measurement = Measurement::open(&args.files)?;
+    for file in measurement.files() {
+        println!("Measurement file    : {}", file.display());
+    }
+    println!(
+        "Duration            : {:.3} s",
+        measurement.duration().as_secs_f64()
+    );
+
+    let mut player = Player::new(&measurement, args.options)?;
+    for topic in player.topics() {
+        println!("Publishing topic    : {topic}");
+    }
+
+    let handle = player.handle();
+    ctrlc::set_handler(move || handle.stop())?;
+
+    thread::sleep(args.delay);
+    let stats = player.play()?;
+    println!("Published messages  : {}", stats.messages);
+    if stats.failed > 0 {
+        println!("Failed messages     : {}", stats.failed);
+    }
+    if stats.loops > 0 {
+        println!("Completed loops     : {}", stats.loops);
+    }
+    Ok(())
+}
@@ -0,0 +1,292 @@
+//! Reading recorded MCAP files.
+
+use crate::error::PlayError;
+use mcap::records::ChunkIndex;
+use mcap::{Channel, McapResult, Message, MessageStream, Summary};
+use memmap2::Mmap;
+use rustecal_core::types::DataTypeInfo;
+use rustecal_tools_common::mcap::{META_ENCODING, META_TYPE_NAME};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// A recorded topic with the data type to publish it with.
+#[derive(Debug, Clone)]
+pub struct ChannelInfo {
+    pub topic_name: String,
+    pub data_type: DataTypeInfo,
+}
+
+/// A recorded message.
+#[derive(Debug, Clone)]
+pub struct MeasurementMessage<'a> {
+    /// Index into [`Measurement::channels`].
+    pub channel: usize,
+    /// Receive time in nanoseconds.
+    pub log_time: u64,
+    /// Send time in nanoseconds.
+    pub publish_time: u64,
+    pub data: Cow<'a, [u8]>,
+}
+
+/// One or more MCAP files, e.g. the parts of a split recording.
+///
+/// Files are memory mapped and played one after the other, ordered by the
+/// time of their first message.
+pub struct Measurement {
+    files: Vec<MeasurementFile>,
+    channels: Vec<ChannelInfo>,
+    start_time: u64,
+    end_time: u64,
+}
+
+struct MeasurementFile {
+    path: PathBuf,
+    data: Mmap,
+    summary: Option<Summary>,
+    /// Maps MCAP channel ids of this file onto indices into `Measurement::channels`.
+    channels: HashMap<u16, usize>,
+    start_time: u64,
+    end_time: u64,
+}
+
+impl Measurement {
+    /// Opens the given files.
+    ///
+    /// Files without summary section (e.g. from an interrupted recording) are
+    /// scanned once to find their channels and time range.
+    ///
+    /// # Errors
+    /// Returns a [`PlayError`] if a file cannot be read or is not valid MCAP.
+    pub fn open<P: AsRef<Path>>(paths: &[P]) -> Result<Self, PlayError> {
+        let mut channels = Vec::new();
+        let mut files = Vec::new();
+        for path in paths {
+            files.push(MeasurementFile::open(path.as_ref(), &mut channels)?);
+        }
+        files.sort_by_key(|file| file.start_time);
+
+        let start_time = files.iter().map(|f| f.start_time).min().unwrap_or(0);
+        let end_time = files.iter().map(|f| f.end_time).max().unwrap_or(0);
+        Ok(Self {
+            files,
+            channels,
+            start_time,
+            end_time,
+        })
+    }
+
+    /// Returns the recorded topics.
+    pub fn channels(&self) -> &[ChannelInfo] {
+        &self.channels
+    }
+
+    /// Returns the opened files in playback order.
+    pub fn files(&self) -> Vec<&Path> {
+        self.files.iter().map(|f| f.path.as_path()).collect()
+    }
+
+    /// Receive time of the first message in nanoseconds.
+    pub fn start_time(&self) -> u64 {
+        self.start_time
+    }
+
+    /// Receive time of the last message in nanoseconds.
+    pub fn end_time(&self) -> u64 {
+        self.end_time
+    }
+
+    /// Time span between first and last message.
+    pub fn duration(&self) -> Duration {
+        Duration::from_nanos(self.end_time.saturating_sub(self.start_time))
+    }
+
+    /// Iterates over all messages received at or after `from` (nanoseconds).
+    ///
+    /// Chunks of indexed files that end before `from` are skipped without
+    /// being decompressed, so seeking is cheap.
+    pub fn messages(&self, from: u64) -> Messages<'_> {
+        Messages {
+            measurement: self,
+            from,
+            next_file: 0,
+            current: None,
+        }
+    }
+}
+
+impl MeasurementFile {
+    fn open(path: &Path, channels: &mut Vec<ChannelInfo>) -> Result<Self, PlayError> {
+        let file = File::open(path)?;
+        // SAFETY: recordings are not expected to be modified while being played
+        let data = unsafe { Mmap::map(&file)? };
+
+        let summary = Summary::read(&data).ok().flatten();
+        let stats = summary.as_ref().and_then(|summary| summary.stats.clone());
+
+        let mut file_channels = HashMap::new();
+        let (start_time, end_time) = match (&summary, stats) {
+            (Some(summary), Some(stats)) => {
+                for channel in summary.channels.values() {
+                    file_channels.insert(channel.id, channel_index(channels, channel));
+                }
+                (stats.message_start_time, stats.message_end_time)
+            }
+            _ => {
+                let mut bounds: Option<(u64, u64)> = None;
+                for message in MessageStream::new(&data)? {
+                    let message = message?;
+                    file_channels
+                        .entry(message.channel.id)
+                        .or_insert_with(|| channel_index(channels, &message.channel));
+                    let (start, end) = bounds.get_or_insert((message.log_time, message.log_time));
+                    *start = (*start).min(message.log_time);
+                    *end = (*end).max(message.log_time);
+                }
+                bounds.unwrap_or_default()
+            }
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            data,
+            summary,
+            channels: file_channels,
+            start_time,
+            end_time,
+        })
+    }
+
+    /// Streams the messages of this file, skipping chunks that end before `from`.
+    fn messages(&self, from: u64) -> Result<MessageIter<'_>, PlayError> {
+        let chunk_indexes = self
+            .summary
+            .as_ref()
+            .map(|summary| summary.chunk_indexes.as_slice())
+            .unwrap_or_default();
+        let Some(summary) = self.summary.as_ref().filter(|_| !chunk_indexes.is_empty()) else {
+            return Ok(Box::new(MessageStream::new(&self.data)?.map(borrowed)));
+        };
+
+        let mut chunks: Vec<&ChunkIndex> = chunk_indexes
+            .iter()
+            .filter(|index| index.message_end_time >= from)
+            .collect();
+        chunks.sort_by_key(|index| index.chunk_start_offset);
+        let data: &[u8] = &self.data;
+        Ok(Box::new(chunks.into_iter().flat_map(
+            move |index| match summary.stream_chunk(data, index) {
+                Ok(messages) => Box::new(messages) as MessageIter<'_>,
+                Err(error) => Box::new(std::iter::once(Err(error))),
+            },
+        )))
+    }
+}
+
+type MessageIter<'a> = Box<dyn Iterator<Item = McapResult<Message<'a>>> + 'a>;
+
+/// Shortens the lifetime of the owned messages yielded by [`MessageStream`].
+fn borrowed<'a>(message: McapResult<Message<'static>>) -> McapResult<Message<'a>> {
+    message
+}
+
+/// Returns the index of the channel with topic and data type of `channel`, adding it if new.
+fn channel_index(channels: &mut Vec<ChannelInfo>, channel: &Channel<'_>) -> usize {
+    let info = channel_info(channel);
+    let known = channels.iter().position(|known| {
+        known.topic_name == info.topic_name
+            && known.data_type.encoding == info.data_type.encoding
+            && known.data_type.type_name == info.data_type.type_name
+    });
+    known.unwrap_or_else(|| {
+        channels.push(info);
+        channels.len() - 1
+    })
+}
+
+/// Restores the eCAL data type of a channel.
+///
+/// Uses the metadata written by `rustecal-record` if present, and falls back
+/// to message encoding and schema for MCAP files from other sources.
+fn channel_info(channel: &Channel<'_>) -> ChannelInfo {
+    let schema = channel.schema.as_ref();
+    let encoding = channel
+        .metadata
+        .get(META_ENCODING)
+        .cloned()
+        .unwrap_or_else(|| ecal_encoding(&channel.message_encoding).to_string());
+    let type_name = channel
+        .metadata
+        .get(META_TYPE_NAME)
+        .cloned()
+        .or_else(|| schema.map(|schema| schema.name.clone()))
+        .unwrap_or_default();
+    let descriptor = schema
+        .map(|schema| schema.data.to_vec())
+        .unwrap_or_default();
+
+    ChannelInfo {
+        topic_name: channel.topic.clone(),
+        data_type: DataTypeInfo {
+            encoding,
+            type_name,
+            descriptor,
+        },
+    }
+}
+
+/// Maps an MCAP message encoding onto an eCAL encoding.
+fn ecal_encoding(message_encoding: &str) -> &str {
+    match message_encoding {
+        "protobuf" => "proto",
+        "flatbuffer" => "flatb",
+        other => other,
+    }
+}
+
+/// Iterator returned by [`Measurement::messages`].
+pub struct Messages<'a> {
+    measurement: &'a Measurement,
+    from: u64,
+    next_file: usize,
+    current: Option<(&'a MeasurementFile, MessageIter<'a>)>,
+}
+
+impl<'a> Iterator for Messages<'a> {
+    type Item = Result<MeasurementMessage<'a>, PlayError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((file, messages)) = &mut self.current {
+                match messages.next() {
+                    Some(Ok(message)) if message.log_time < self.from => continue,
+                    Some(Ok(message)) => {
+                        let Some(&channel) = file.channels.get(&message.channel.id) else {
+                            continue;
+                        };
+                        return Some(Ok(MeasurementMessage {
+                            channel,
+                            log_time: message.log_time,
+                            publish_time: message.publish_time,
+                            data: message.data,
+                        }));
+                    }
+                    Some(Err(error)) => return Some(Err(error.into())),
+                    None => self.current = None,
+                }
+            }
+
+            let file = self.measurement.files.get(self.next_file)?;
+            self.next_file += 1;
+            if file.end_time < self.from {
+                continue;
+            }
+            match file.messages(self.from) {
+                Ok(messages) => self.current = Some((file, messages)),
+                Err(error) => return Some(Err(error)),
+            }
+        }
+    }
+}
@@ -0,0 +1,449 @@
+//! Republishing a [`Measurement`] with its original timing.
+
+use crate::error::PlayError;
+use crate::measurement::Measurement;
+use rustecal_pubsub::Publisher;
+use rustecal_pubsub::publisher::Timestamp;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// Playback settings of a [`Player`].
+#[derive(Debug, Clone)]
+pub struct PlayOptions {
+    /// Speed factor; `2.0` plays twice as fast, `f64::INFINITY` as fast as possible.
+    ///
+    /// Must be positive, see [`PlayOptions::validate`].
+    pub rate: f64,
+    /// Restart at the beginning of the range after the last message.
+    pub looping: bool,
+    /// Offset into the measurement to start at.
+    pub start: Option<Duration>,
+    /// Offset into the measurement to stop at.
+    pub end: Option<Duration>,
+    /// Topics to play, all if `None`.
+    pub topics: Option<HashSet<String>>,
+    /// Recorded topic name → topic name to publish on.
+    pub remap: HashMap<String, String>,
+}
+
+impl Default for PlayOptions {
+    fn default() -> Self {
+        Self {
+            rate: 1.0,
+            looping: false,
+            start: None,
+            end: None,
+            topics: None,
+            remap: HashMap::new(),
+        }
+    }
+}
+
+impl PlayOptions {
+    /// Sets the speed factor, which must be positive.
+    ///
+    /// [`Player::new`] rejects other values with [`PlayError::InvalidRate`].
+    pub fn with_rate(mut self, rate: f64) -> Self {
+        self.rate = rate;
+        self
+    }
+
+    /// Enables or disables looping.
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// Limits playback to the given offsets into the measurement.
+    pub fn with_range(mut self, start: Option<Duration>, end: Option<Duration>) -> Self {
+        self.start = start;
+        self.end = end;
+        self
+    }
+
+    /// Plays only the given topics.
+    pub fn with_topics<I, S>(mut self, topics: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.topics = Some(topics.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Publishes the recorded topic `from` as `to`.
+    pub fn with_remap(mut self, from: impl Into<String>, to: impl Into<String>) -> Self {
+        self.remap.insert(from.into(), to.into());
+        self
+    }
+
+    /// Checks the options, as done by [`Player::new`].
+    ///
+    /// # Errors
+    /// Returns [`PlayError::InvalidRate`] if the rate is not positive.
+    pub fn validate(&self) -> Result<(), PlayError> {
+        validate_rate(self.rate)
+    }
+}
+
+/// Counters returned by [`Player::play`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PlayStats {
+    /// Messages handed to eCAL.
+    pub messages: u64,
+    /// Messages eCAL failed to send.
+    pub failed: u64,
+    /// Completed loops.
+    pub loops: u64,
+}
+
+/// Publishes the messages of a [`Measurement`] with their original timing.
+///
+/// ```ignore
+/// let measurement = Measurement::open(&["run_000.mcap", "run_001.mcap"])?;
+/// let mut player = Player::new(&measurement, PlayOptions::default().with_rate(2.0))?;
+/// let handle = player.handle();
+/// ctrlc::set_handler(move || handle.stop())?;
+/// player.play()?;
+/// ```
+///
+/// One publisher is created per topic and data type when the player is
+/// created, with the data type of the recording. Timing follows the receive
+/// timestamps of the recording.
+pub struct Player<'m> {
+    measurement: &'m Measurement,
+    options: PlayOptions,
+    publishers: Vec<(String, Publisher)>,
+    /// Publisher index per channel of the measurement, `None` if not played.
+    routes: Vec<Option<usize>>,
+    control: Arc<Control>,
+}
+
+impl<'m> Player<'m> {
+    /// Creates the publishers for all selected topics.
+    ///
+    /// # Errors
+    /// Returns a [`PlayError`] if the rate is not positive or a publisher
+    /// cannot be created.
+    pub fn new(measurement: &'m Measurement, options: PlayOptions) -> Result<Self, PlayError> {
+        options.validate()?;
+
+        let mut publishers: Vec<(String, Publisher)> = Vec::new();
+        let mut by_type: HashMap<(String, String, String), usize> = HashMap::new();
+        let mut routes = Vec::new();
+
+        for channel in measurement.channels() {
+            let selected = options
+                .topics
+                .as_ref()
+                .is_none_or(|topics| topics.contains(&channel.topic_name));
+            if !selected {
+                routes.push(None);
+                continue;
+            }
+
+            let topic = options
+                .remap
+                .get(&channel.topic_name)
+                .unwrap_or(&channel.topic_name)
+                .clone();
+            let key = (
+                topic.clone(),
+                channel.data_type.encoding.clone(),
+                channel.data_type.type_name.clone(),
+            );
+            let index = match by_type.get(&key) {
+                Some(&index) => index,
+                None => {
+                    let publisher =
+                        Publisher::new(&topic, channel.data_type.clone()).map_err(|reason| {
+                            PlayError::Publish {
+                                topic: topic.clone(),
+                                reason,
+                            }
+                        })?;
+                    publishers.push((topic, publisher));
+                    by_type.insert(key, publishers.len() - 1);
+                    publishers.len() - 1
+                }
+            };
+            routes.push(Some(index));
+        }
+
+        let control = Arc::new(Control::new(options.rate));
+        Ok(Self {
+            measurement,
+            options,
+            publishers,
+            routes,
+            control,
+        })
+    }
+
+    /// Returns the topics published on, sorted by name.
+    pub fn topics(&self) -> Vec<String> {
+        let mut topics: Vec<String> = self.publishers.iter().map(|(t, _)| t.clone()).collect();
+        topics.sort();
+        topics.dedup();
+        topics
+    }
+
+    /// Returns a handle to control a running [`Player::play`] from other threads.
+    pub fn handle(&self) -> PlayerHandle {
+        PlayerHandle {
+            control: self.control.clone(),
+        }
+    }
+
+    /// Plays the selected range, blocking until it is finished or stopped.
+    ///
+    /// Returns immediately once [`PlayerHandle::stop`] was called, also if it
+    /// was called before playing.
+    ///
+    /// # Errors
+    /// Returns a [`PlayError`] if the measurement cannot be read.
+    pub fn play(&mut self) -> Result<PlayStats, PlayError> {
+        let first = self.measurement.start_time();
+        let offset = |duration: Duration| first.saturating_add(duration.as_nanos() as u64);
+        let range_start = self.options.start.map_or(first, offset);
+        let range_end = self.options.end.map_or(u64::MAX, offset);
+
+        self.control.reset();
+        let mut stats = PlayStats::default();
+        let mut position = range_start;
+        let mut seen = self.control.generation();
+
+        'play: loop {
+            // playback time and wall clock of the message timing is relative to
+            let mut anchor: Option<(u64, Instant)> = None;
+            let mut played = false;
+
+            for message in self.measurement.messages(position) {
+                let message = message?;
+                if message.log_time > range_end {
+                    break;
+                }
+                let Some(index) = self.routes[message.channel] else {
+                    continue;
+                };
+
+                loop {
+                    let (time, instant) =
+                        *anchor.get_or_insert_with(|| (message.log_time, Instant::now()));
+                    let delay = Duration::from_nanos(message.log_time.saturating_sub(time));
+                    match self.control.wait(instant, delay, &mut seen) {
+                        Wait::Ready => break,
+                        Wait::Changed => anchor = None,
+                        Wait::Seek(to) => {
+                            position = offset(to).max(range_start);
+                            continue 'play;
+                        }
+                        Wait::Stopped => return Ok(stats),
+                    }
+                }
+
+                played = true;
+                if self.publishers[index]
+                    .1
+                    .send(&message.data, Timestamp::Auto)
+                {
+                    stats.messages += 1;
+                } else {
+                    stats.failed += 1;
+                }
+                self.control
+                    .set_position(Duration::from_nanos(message.log_time - first));
+            }
+
+            // nothing to loop over if the range holds no selected messages
+            if !self.options.looping || !played {
+                return Ok(stats);
+            }
+            stats.loops += 1;
+            position = range_start;
+        }
+    }
+}
+
+/// Controls a running [`Player`]. Cheap to clone and usable from any thread.
+#[derive(Clone)]
+pub struct PlayerHandle {
+    control: Arc<Control>,
+}
+
+impl PlayerHandle {
+    /// Pauses playback before the next message.
+    pub fn pause(&self) {
+        self.control.update(|state| state.paused = true);
+    }
+
+    /// Resumes playback; the next message is sent immediately.
+    pub fn resume(&self) {
+        self.control.update(|state| state.paused = false);
+    }
+
+    /// Returns whether playback is paused.
+    pub fn is_paused(&self) -> bool {
+        self.control.state.lock().unwrap().paused
+    }
+
+    /// Continues playback at the given offset into the measurement.
+    pub fn seek(&self, offset: Duration) {
+        self.control.update(|state| state.seek = Some(offset));
+    }
+
+    /// Changes the speed factor.
+    ///
+    /// # Errors
+    /// Returns [`PlayError::InvalidRate`] and keeps the current rate if `rate`
+    /// is not positive.
+    pub fn set_rate(&self, rate: f64) -> Result<(), PlayError> {
+        validate_rate(rate)?;
+        self.control.update(|state| state.rate = rate);
+        Ok(())
+    }
+
+    /// Stops playback; [`Player::play`] returns.
+    pub fn stop(&self) {
+        self.control.update(|state| state.stopped = true);
+    }
+
+    /// Returns the offset of the last sent message into the measurement.
+    pub fn position(&self) -> Duration {
+        self.control.state.lock().unwrap().position
+    }
+}
+
+/// Rejects rates that play backwards or not at all.
+fn validate_rate(rate: f64) -> Result<(), PlayError> {
+    if rate > 0.0 {
+        Ok(())
+    } else {
+        Err(PlayError::InvalidRate(rate))
+    }
+}
+
+/// Outcome of waiting for the send time of a message.
+enum Wait {
+    /// The send time is reached.
+    Ready,
+    /// Pause state or rate changed; timing has to be re-anchored.
+    Changed,
+    Seek(Duration),
+    Stopped,
+}
+
+struct ControlState {
+    paused: bool,
+    stopped: bool,
+    seek: Option<Duration>,
+    rate: f64,
+    position: Duration,
+    /// Incremented on every change, so a waiting player notices it.
+    generation: u64,
+}
+
+/// Playback state shared between [`Player`] and its handles.
+struct Control {
+    state: Mutex<ControlState>,
+    changed: Condvar,
+}
+
+impl Control {
+    fn new(rate: f64) -> Self {
+        Self {
+            state: Mutex::new(ControlState {
+                paused: false,
+                stopped: false,
+                seek: None,
+                rate,
+                position: Duration::ZERO,
+                generation: 0,
+            }),
+            changed: Condvar::new(),
+        }
+    }
+
+    fn update(&self, change: impl FnOnce(&mut ControlState)) {
+        let mut state = self.state.lock().unwrap();
+        change(&mut state);
+        state.generation += 1;
+        self.changed.notify_all();
+    }
+
+    fn generation(&self) -> u64 {
+        self.state.lock().unwrap().generation
+    }
+
+    /// Clears a seek requested before playing.
+    fn reset(&self) {
+        self.update(|state| {
+            state.seek = None;
+            state.position = Duration::ZERO;
+        });
+    }
+
+    fn set_position(&self, position: Duration) {
+        self.state.lock().unwrap().position = position;
+    }
+
+    /// Waits until `delay`, scaled by the rate, has passed since `anchor`.
+    ///
+    /// A send time too far ahead to represent, e.g. at a tiny rate, is never
+    /// reached; only a change of the playback state ends the wait.
+    fn wait(&self, anchor: Instant, delay: Duration, seen: &mut u64) -> Wait {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if state.stopped {
+                return Wait::Stopped;
+            }
+            if let Some(offset) = state.seek.take() {
+                *seen = state.generation;
+                return Wait::Seek(offset);
+            }
+            if state.generation != *seen {
+                *seen = state.generation;
+                return Wait::Changed;
+            }
+            if state.paused {
+                state = self.changed.wait(state).unwrap();
+                continue;
+            }
+
+            let target = Duration::try_from_secs_f64(delay.as_secs_f64() / state.rate)
+                .ok()
+                .and_then(|delay| anchor.checked_add(delay));
+            let Some(target) = target else {
+                state = self.changed.wait(state).unwrap();
+                continue;
+            };
+            let now = Instant::now();
+            if now >= target {
+                return Wait::Ready;
+            }
+            state = self.changed.wait_timeout(state, target - now).unwrap().0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn tiny_rate_waits_without_overflowing() {
+        let control = Arc::new(Control::new(1e-20));
+        let stopper = control.clone();
+        let stop = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            stopper.update(|state| state.stopped = true);
+        });
+
+        let mut seen = control.generation();
+        let wait = control.wait(Instant::now(), Duration::from_secs(1), &mut seen);
+        assert!(matches!(wait, Wait::Stopped));
+        stop.join().unwrap();
+    }
+}
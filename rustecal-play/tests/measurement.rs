@@ -0,0 +1,141 @@
+use rustecal_core::types::DataTypeInfo;
+use rustecal_play::{Measurement, PlayError, PlayOptions};
+use rustecal_record::{Compression, McapWriter, RecordedMessage, Split};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Returns an empty directory for the output of one test.
+fn output_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rustecal-play-{}-{test}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn proto_type() -> DataTypeInfo {
+    DataTypeInfo {
+        encoding: "proto".to_string(),
+        type_name: "pb.People.Person".to_string(),
+        descriptor: vec![1, 2, 3, 4],
+    }
+}
+
+fn string_type() -> DataTypeInfo {
+    DataTypeInfo {
+        encoding: "utf-8".to_string(),
+        type_name: "string".to_string(),
+        descriptor: Vec::new(),
+    }
+}
+
+/// Records `count` messages, alternating between a proto and a string topic,
+/// one every 100 ms starting at 1 s.
+fn record(path: &PathBuf, split: Split, count: i64) -> Vec<PathBuf> {
+    let person = proto_type();
+    let string = string_type();
+    let mut writer = McapWriter::create(path, split, Some(Compression::Zstd)).unwrap();
+    for i in 0..count {
+        let (topic_name, data_type) = if i % 2 == 0 {
+            ("person", &person)
+        } else {
+            ("hello", &string)
+        };
+        let time = 1_000_000 + i * 100_000;
+        writer
+            .write(&RecordedMessage {
+                topic_name,
+                data_type,
+                send_timestamp: time,
+                receive_timestamp: time,
+                clock: i,
+                data: &[i as u8],
+            })
+            .unwrap();
+    }
+    writer.finish().unwrap()
+}
+
+#[test]
+fn restores_channels_and_data_types() {
+    let path = output_dir("channels").join("run.mcap");
+    let files = record(&path, Split::default(), 4);
+
+    let measurement = Measurement::open(&files).unwrap();
+    let channels = measurement.channels();
+    assert_eq!(channels.len(), 2);
+
+    let person = channels.iter().find(|c| c.topic_name == "person").unwrap();
+    assert_eq!(person.data_type.encoding, "proto");
+    assert_eq!(person.data_type.type_name, "pb.People.Person");
+    assert_eq!(person.data_type.descriptor, vec![1, 2, 3, 4]);
+
+    let hello = channels.iter().find(|c| c.topic_name == "hello").unwrap();
+    assert_eq!(hello.data_type.encoding, "utf-8");
+    assert_eq!(hello.data_type.type_name, "string");
+    assert!(hello.data_type.descriptor.is_empty());
+}
+
+#[test]
+fn reads_split_recordings_in_order() {
+    let path = output_dir("split").join("run.mcap");
+    let files = record(&path, Split::by_duration(Duration::from_millis(300)), 10);
+    assert!(files.len() > 1);
+
+    // open in reverse to check the files are ordered by time
+    let reversed: Vec<PathBuf> = files.iter().rev().cloned().collect();
+    let measurement = Measurement::open(&reversed).unwrap();
+    assert_eq!(
+        measurement.files(),
+        files.iter().map(PathBuf::as_path).collect::<Vec<_>>()
+    );
+    assert_eq!(measurement.channels().len(), 2);
+    assert_eq!(measurement.start_time(), 1_000_000_000);
+    assert_eq!(measurement.end_time(), 1_900_000_000);
+    assert_eq!(measurement.duration(), Duration::from_millis(900));
+
+    let data: Vec<u8> = measurement
+        .messages(0)
+        .map(|message| message.unwrap().data[0])
+        .collect();
+    assert_eq!(data, (0..10).collect::<Vec<u8>>());
+}
+
+#[test]
+fn starts_messages_at_the_given_time() {
+    let path = output_dir("seek").join("run.mcap");
+    let files = record(&path, Split::by_duration(Duration::from_millis(300)), 10);
+    let measurement = Measurement::open(&files).unwrap();
+
+    let messages: Vec<_> = measurement
+        .messages(1_450_000_000)
+        .map(Result::unwrap)
+        .collect();
+    let times: Vec<u64> = messages.iter().map(|m| m.log_time).collect();
+    assert_eq!(
+        times,
+        (5..10)
+            .map(|i| 1_000_000_000 + i * 100_000_000)
+            .collect::<Vec<u64>>()
+    );
+
+    let channels = measurement.channels();
+    assert_eq!(channels[messages[0].channel].topic_name, "hello");
+    assert_eq!(channels[messages[1].channel].topic_name, "person");
+}
+
+#[test]
+fn non_positive_rate_is_rejected() {
+    for rate in [0.0, -1.0, f64::NAN] {
+        let options = PlayOptions {
+            rate,
+            ..PlayOptions::default()
+        };
+        assert!(matches!(options.validate(), Err(PlayError::InvalidRate(_))));
+    }
+    assert!(
+        PlayOptions::default()
+            .with_rate(f64::INFINITY)
+            .validate()
+            .is_ok()
+    );
+}
@@ -0,0 +1,31 @@
+//! Error types of the typed publish/subscribe layer.
+
+use std::error::Error;
+use thiserror::Error;
+
+/// A message could not be serialized into its wire format.
+///
+/// Wraps the error reported by the underlying encoder (e.g. `serde_json`,
+/// `prost`), so the cause is preserved for logging.
+#[derive(Debug, Error)]
+#[error("message serialization failed: {0}")]
+pub struct SerializationError(Box<dyn Error + Send + Sync + 'static>);
+
+impl SerializationError {
+    /// Wraps an encoder error or a plain error message.
+    pub fn new(error: impl Into<Box<dyn Error + Send + Sync + 'static>>) -> Self {
+        Self(error.into())
+    }
+}
+
+/// Errors returned by [`TypedPublisher::try_send`](crate::TypedPublisher::try_send).
+#[derive(Debug, Error)]
+pub enum PublishError {
+    /// The message could not be serialized; nothing was sent.
+    #[error(transparent)]
+    Serialization(#[from] SerializationError),
+
+    /// eCAL rejected the serialized payload.
+    #[error("eCAL failed to send the message")]
+    Send,
+}
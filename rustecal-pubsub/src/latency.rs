@@ -0,0 +1,335 @@
+//! End-to-end latency statistics.
+//!
+//! A [`LatencyRecorder`] compares the send timestamp of every received message
+//! with the eCAL time at receive and collects the difference in histograms per
+//! topic and publisher. Histograms use log-linear buckets like HdrHistogram,
+//! so percentiles keep a relative error below 2% over the full value range at
+//! constant memory.
+
+use crate::typed_subscriber::Received;
+use rustecal_core::log::Log;
+use rustecal_core::log_level::LogLevel;
+use rustecal_core::time::Time;
+use rustecal_core::types::EntityId;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Number of linear sub-buckets per power of two, as bits.
+const SUB_BUCKET_BITS: u32 = 6;
+const SUB_BUCKETS: u64 = 1 << SUB_BUCKET_BITS;
+/// Buckets needed to cover the full `u64` range.
+const BUCKETS: usize = ((64 - SUB_BUCKET_BITS) as usize + 1) * SUB_BUCKETS as usize;
+
+/// Summary of a latency histogram. All values are in microseconds.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct LatencyStats {
+    /// Number of recorded samples.
+    pub count: u64,
+    /// Smallest recorded latency.
+    pub min: u64,
+    /// Largest recorded latency.
+    pub max: u64,
+    /// Arithmetic mean of all samples.
+    pub mean: f64,
+    /// Median.
+    pub p50: u64,
+    /// 99th percentile.
+    pub p99: u64,
+    /// 99.9th percentile.
+    pub p999: u64,
+}
+
+/// Histogram of latencies in microseconds.
+#[derive(Debug, Clone)]
+pub struct LatencyHistogram {
+    buckets: Vec<u64>,
+    count: u64,
+    min: u64,
+    max: u64,
+    sum: u128,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            buckets: vec![0; BUCKETS],
+            count: 0,
+            min: u64::MAX,
+            max: 0,
+            sum: 0,
+        }
+    }
+}
+
+impl LatencyHistogram {
+    /// Creates an empty histogram.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records one latency sample.
+    pub fn record(&mut self, latency_us: u64) {
+        self.buckets[bucket_index(latency_us)] += 1;
+        self.count += 1;
+        self.min = self.min.min(latency_us);
+        self.max = self.max.max(latency_us);
+        self.sum += u128::from(latency_us);
+    }
+
+    /// Adds all samples of `other` to this histogram.
+    pub fn merge(&mut self, other: &LatencyHistogram) {
+        for (bucket, count) in self.buckets.iter_mut().zip(&other.buckets) {
+            *bucket += count;
+        }
+        self.count += other.count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.sum += other.sum;
+    }
+
+    /// Returns the number of recorded samples.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns the latency below or at which `percentile` percent of the samples lie.
+    ///
+    /// The result is the upper bound of the matching bucket, capped at the
+    /// recorded maximum. Returns 0 for an empty histogram.
+    pub fn percentile(&self, percentile: f64) -> u64 {
+        if self.count == 0 {
+            return 0;
+        }
+        let rank = ((percentile.clamp(0.0, 100.0) / 100.0) * self.count as f64).ceil() as u64;
+        let rank = rank.max(1);
+
+        let mut seen = 0;
+        for (index, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return bucket_upper_bound(index).clamp(self.min, self.max);
+            }
+        }
+        self.max
+    }
+
+    /// Returns min, max, mean and the common percentiles.
+    pub fn stats(&self) -> LatencyStats {
+        if self.count == 0 {
+            return LatencyStats::default();
+        }
+        LatencyStats {
+            count: self.count,
+            min: self.min,
+            max: self.max,
+            mean: self.sum as f64 / self.count as f64,
+            p50: self.percentile(50.0),
+            p99: self.percentile(99.0),
+            p999: self.percentile(99.9),
+        }
+    }
+}
+
+/// Returns the bucket of `value`.
+///
+/// Values below `2 * SUB_BUCKETS` get a bucket each; above, every power of two
+/// is split into `SUB_BUCKETS` buckets of equal width.
+fn bucket_index(value: u64) -> usize {
+    if value < 2 * SUB_BUCKETS {
+        return value as usize;
+    }
+    let shift = 63 - value.leading_zeros() - SUB_BUCKET_BITS;
+    (u64::from(shift) * SUB_BUCKETS + (value >> shift)) as usize
+}
+
+/// Returns the largest value that falls into bucket `index`.
+fn bucket_upper_bound(index: usize) -> u64 {
+    let index = index as u64;
+    if index < 2 * SUB_BUCKETS {
+        return index;
+    }
+    let shift = index / SUB_BUCKETS - 1;
+    let sub_bucket = index - shift * SUB_BUCKETS;
+    ((sub_bucket + 1) << shift).wrapping_sub(1)
+}
+
+/// Latency summary of one publisher on one topic, as returned by [`LatencyRecorder::report`].
+#[derive(Debug, Clone)]
+pub struct LatencyReport {
+    pub topic_name: String,
+    pub publisher: EntityId,
+    pub stats: LatencyStats,
+}
+
+impl fmt::Display for LatencyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stats = &self.stats;
+        write!(
+            f,
+            "latency {} [{}:{}:{}] n={} min={}us mean={:.1}us p50={}us p99={}us p99.9={}us max={}us",
+            self.topic_name,
+            self.publisher.host_name,
+            self.publisher.process_id,
+            self.publisher.entity_id,
+            stats.count,
+            stats.min,
+            stats.mean,
+            stats.p50,
+            stats.p99,
+            stats.p999,
+            stats.max,
+        )
+    }
+}
+
+/// Collects end-to-end latencies per topic and publisher.
+///
+/// ```ignore
+/// let recorder = Arc::new(LatencyRecorder::new());
+/// subscriber.set_latency_recorder(recorder.clone());
+/// let _logger = recorder.clone().log_every(Duration::from_secs(10));
+/// // ...
+/// println!("{:?}", recorder.topic_stats("Performance"));
+/// ```
+///
+/// Latencies are only meaningful if sender and receiver share the time base,
+/// i.e. run on the same host or use an eCAL time synchronization plugin.
+/// Negative latencies caused by clock offsets are recorded as 0.
+#[derive(Default)]
+pub struct LatencyRecorder {
+    /// Histograms per topic, then per publisher, so that recording a sample
+    /// for a known publisher looks both up by reference without allocating.
+    histograms: Mutex<HashMap<String, HashMap<EntityId, LatencyHistogram>>>,
+}
+
+impl LatencyRecorder {
+    /// Creates an empty recorder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the latency of a message sent at `send_timestamp` (eCAL time in
+    /// microseconds), measured against the current eCAL time.
+    pub fn record(&self, topic_name: &str, publisher: &EntityId, send_timestamp: i64) {
+        let latency = Time::get_microseconds() - send_timestamp;
+        self.record_latency(topic_name, publisher, latency.max(0) as u64);
+    }
+
+    /// Records the latency of a received message, measured now.
+    ///
+    /// Prefer attaching the recorder to the subscriber, which measures before
+    /// the payload is decoded.
+    pub fn record_received<T>(&self, received: &Received<T>) {
+        self.record(
+            &received.topic_name,
+            &received.publisher_id,
+            received.timestamp,
+        );
+    }
+
+    /// Records an already measured latency in microseconds.
+    pub fn record_latency(&self, topic_name: &str, publisher: &EntityId, latency_us: u64) {
+        let mut histograms = self.histograms.lock().unwrap();
+        let publishers = match histograms.get_mut(topic_name) {
+            Some(publishers) => publishers,
+            None => histograms.entry(topic_name.to_string()).or_default(),
+        };
+        match publishers.get_mut(publisher) {
+            Some(histogram) => histogram.record(latency_us),
+            None => publishers
+                .entry(publisher.clone())
+                .or_default()
+                .record(latency_us),
+        }
+    }
+
+    /// Returns the statistics of one publisher on a topic.
+    pub fn publisher_stats(&self, topic_name: &str, publisher: &EntityId) -> Option<LatencyStats> {
+        let histograms = self.histograms.lock().unwrap();
+        histograms
+            .get(topic_name)
+            .and_then(|publishers| publishers.get(publisher))
+            .map(LatencyHistogram::stats)
+    }
+
+    /// Returns the statistics of a topic over all its publishers.
+    pub fn topic_stats(&self, topic_name: &str) -> Option<LatencyStats> {
+        self.topic_histogram(topic_name).map(|h| h.stats())
+    }
+
+    /// Returns the merged histogram of a topic over all its publishers.
+    pub fn topic_histogram(&self, topic_name: &str) -> Option<LatencyHistogram> {
+        let histograms = self.histograms.lock().unwrap();
+        let mut merged: Option<LatencyHistogram> = None;
+        for histogram in histograms.get(topic_name)?.values() {
+            merged
+                .get_or_insert_with(LatencyHistogram::new)
+                .merge(histogram);
+        }
+        merged
+    }
+
+    /// Returns the statistics of every topic and publisher, sorted by topic name.
+    pub fn report(&self) -> Vec<LatencyReport> {
+        let histograms = self.histograms.lock().unwrap();
+        let mut report: Vec<LatencyReport> = histograms
+            .iter()
+            .flat_map(|(topic_name, publishers)| {
+                publishers
+                    .iter()
+                    .map(move |(publisher, histogram)| LatencyReport {
+                        topic_name: topic_name.clone(),
+                        publisher: publisher.clone(),
+                        stats: histogram.stats(),
+                    })
+            })
+            .collect();
+        report.sort_by(|a, b| {
+            (&a.topic_name, a.publisher.entity_id).cmp(&(&b.topic_name, b.publisher.entity_id))
+        });
+        report
+    }
+
+    /// Discards all samples.
+    pub fn reset(&self) {
+        self.histograms.lock().unwrap().clear();
+    }
+
+    /// Writes the current [`report`](Self::report) to the eCAL log every
+    /// `interval`, until the returned [`LatencyLogger`] is dropped.
+    pub fn log_every(self: Arc<Self>, interval: Duration) -> LatencyLogger {
+        let (stop, stopped) = mpsc::channel::<()>();
+        let handle = thread::spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                for entry in self.report() {
+                    Log::log(LogLevel::Info, &entry.to_string());
+                }
+            }
+        });
+        LatencyLogger {
+            stop: Some(stop),
+            handle: Some(handle),
+        }
+    }
+}
+
+/// Background thread created by [`LatencyRecorder::log_every`].
+///
+/// Stops logging when dropped.
+pub struct LatencyLogger {
+    stop: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Drop for LatencyLogger {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
@@ -13,21 +13,31 @@
 //! - Supported types: `StringMessage`, `BytesMessage`, `ProtobufMessage<T>`
 
 // Re-export core init & types
+pub use rustecal_core::types::DataTypeInfo;
 pub use rustecal_core::{Ecal, EcalComponents};
 
 // Sub‑modules
+pub mod error;
+pub mod latency;
 pub mod payload_writer;
 pub mod publisher;
+pub mod sequence;
 pub mod subscriber;
 pub mod typed_publisher;
 pub mod typed_subscriber;
 pub mod types;
 
 // Public API
+pub use error::{PublishError, SerializationError};
+pub use latency::{LatencyRecorder, LatencyStats};
 pub use payload_writer::PayloadWriter;
 pub use publisher::Publisher;
+pub use sequence::{SequenceEvent, SequenceStats, SequenceTracker};
 pub use subscriber::Subscriber;
 pub use typed_publisher::PublisherMessage;
-pub use typed_publisher::TypedPublisher;
+pub use typed_publisher::{SendContext, TypedPublisher};
 pub use typed_subscriber::SubscriberMessage;
-pub use typed_subscriber::TypedSubscriber;
+pub use typed_subscriber::{ReceiveContext, TypedSubscriber};
+
+#[cfg(feature = "derive")]
+pub use rustecal_derive::EcalMessage;
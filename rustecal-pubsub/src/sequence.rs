@@ -0,0 +1,236 @@
+//! Detection of lost, duplicated and reordered messages.
+//!
+//! eCAL stamps every sent message with the publisher's send clock, which
+//! increases by one per message. A [`SequenceTracker`] follows that clock per
+//! publishing entity and classifies every received message.
+
+use rustecal_core::types::EntityId;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Number of clocks below the newest one that are remembered per publisher.
+///
+/// Late messages within this window are classified as reordered or duplicate;
+/// older ones can no longer be told apart and are only counted as late.
+const WINDOW: i64 = 64;
+
+/// Send clock of the first message of a publisher.
+const FIRST_CLOCK: i64 = 1;
+
+/// Irregularity in the message sequence of one publisher.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SequenceEvent {
+    /// Messages between `expected` and `received` (exclusive) are missing.
+    Gap {
+        publisher: EntityId,
+        expected: i64,
+        received: i64,
+    },
+    /// A message with this clock was already received.
+    Duplicate { publisher: EntityId, clock: i64 },
+    /// A message arrived after a newer one. It was counted as lost before.
+    Reordered { publisher: EntityId, clock: i64 },
+    /// A message arrived further behind the newest one than the tracking
+    /// window reaches, so it may be reordered or a duplicate.
+    Late { publisher: EntityId, clock: i64 },
+    /// The clock jumped back to the start.
+    ///
+    /// The publisher is assumed to have restarted and tracking starts over.
+    /// A publisher recreated under a new entity id is tracked separately anyway.
+    Restarted { publisher: EntityId, clock: i64 },
+}
+
+/// Counters kept by a [`SequenceTracker`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SequenceStats {
+    /// Received messages, including duplicates.
+    pub received: u64,
+    /// Messages that were skipped and have not arrived (yet).
+    pub lost: u64,
+    /// Messages received more than once.
+    pub duplicates: u64,
+    /// Messages received after a newer one.
+    pub reordered: u64,
+    /// Messages received too far behind the newest one to classify; they
+    /// are not subtracted from `lost`.
+    pub late: u64,
+    /// Detected publisher restarts.
+    pub restarts: u64,
+}
+
+impl SequenceStats {
+    fn add(&mut self, other: &SequenceStats) {
+        self.received += other.received;
+        self.lost += other.lost;
+        self.duplicates += other.duplicates;
+        self.reordered += other.reordered;
+        self.late += other.late;
+        self.restarts += other.restarts;
+    }
+}
+
+/// Tracking state of one publisher.
+struct PublisherState {
+    /// Newest clock seen.
+    newest: i64,
+    /// Bit `n` is set if clock `newest - n` was received.
+    seen: u64,
+    stats: SequenceStats,
+}
+
+impl PublisherState {
+    fn new(clock: i64) -> Self {
+        Self {
+            newest: clock,
+            seen: 1,
+            stats: SequenceStats {
+                received: 1,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn track(&mut self, publisher: &EntityId, clock: i64) -> Option<SequenceEvent> {
+        self.stats.received += 1;
+
+        if clock > self.newest {
+            let expected = self.newest + 1;
+            let shift = clock - self.newest;
+            self.seen = if shift >= WINDOW {
+                1
+            } else {
+                (self.seen << shift) | 1
+            };
+            self.newest = clock;
+
+            if clock == expected {
+                return None;
+            }
+            self.stats.lost += (clock - expected) as u64;
+            return Some(SequenceEvent::Gap {
+                publisher: publisher.clone(),
+                expected,
+                received: clock,
+            });
+        }
+
+        // a restarted publisher counts from the first clock again
+        let age = self.newest - clock;
+        if clock <= FIRST_CLOCK && age > 0 {
+            self.newest = clock;
+            self.seen = 1;
+            self.stats.restarts += 1;
+            return Some(SequenceEvent::Restarted {
+                publisher: publisher.clone(),
+                clock,
+            });
+        }
+        if age >= WINDOW {
+            self.stats.late += 1;
+            return Some(SequenceEvent::Late {
+                publisher: publisher.clone(),
+                clock,
+            });
+        }
+
+        let bit = 1u64 << age;
+        if self.seen & bit != 0 {
+            self.stats.duplicates += 1;
+            return Some(SequenceEvent::Duplicate {
+                publisher: publisher.clone(),
+                clock,
+            });
+        }
+        self.seen |= bit;
+        self.stats.reordered += 1;
+        self.stats.lost = self.stats.lost.saturating_sub(1);
+        Some(SequenceEvent::Reordered {
+            publisher: publisher.clone(),
+            clock,
+        })
+    }
+}
+
+type EventCallback = Box<dyn Fn(&SequenceEvent) + Send + Sync>;
+
+/// Tracks the send clock of every publisher on a topic.
+///
+/// Attach it to a [`TypedSubscriber`](crate::TypedSubscriber) with
+/// `set_sequence_tracker`, or feed it manually via [`SequenceTracker::track`].
+///
+/// ```ignore
+/// let tracker = Arc::new(SequenceTracker::with_callback(|event| eprintln!("{event:?}")));
+/// subscriber.set_sequence_tracker(tracker.clone());
+/// // ...
+/// println!("lost: {}", tracker.stats().lost);
+/// ```
+#[derive(Default)]
+pub struct SequenceTracker {
+    publishers: Mutex<HashMap<EntityId, PublisherState>>,
+    callback: Option<EventCallback>,
+}
+
+impl SequenceTracker {
+    /// Creates a tracker that only counts.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a tracker that also reports every irregularity to `callback`.
+    pub fn with_callback<F>(callback: F) -> Self
+    where
+        F: Fn(&SequenceEvent) + Send + Sync + 'static,
+    {
+        Self {
+            publishers: Mutex::default(),
+            callback: Some(Box::new(callback)),
+        }
+    }
+
+    /// Records a message with send clock `clock` from `publisher`.
+    ///
+    /// Returns the detected irregularity, if any; it is also passed to the callback.
+    pub fn track(&self, publisher: &EntityId, clock: i64) -> Option<SequenceEvent> {
+        let event = {
+            let mut publishers = self.publishers.lock().unwrap();
+            match publishers.get_mut(publisher) {
+                Some(state) => state.track(publisher, clock),
+                None => {
+                    publishers.insert(publisher.clone(), PublisherState::new(clock));
+                    None
+                }
+            }
+        };
+
+        if let (Some(event), Some(callback)) = (&event, &self.callback) {
+            callback(event);
+        }
+        event
+    }
+
+    /// Returns the counters summed over all publishers.
+    pub fn stats(&self) -> SequenceStats {
+        let publishers = self.publishers.lock().unwrap();
+        let mut total = SequenceStats::default();
+        for state in publishers.values() {
+            total.add(&state.stats);
+        }
+        total
+    }
+
+    /// Returns the counters of one publisher, or `None` if nothing was received from it.
+    pub fn publisher_stats(&self, publisher: &EntityId) -> Option<SequenceStats> {
+        let publishers = self.publishers.lock().unwrap();
+        publishers.get(publisher).map(|state| state.stats)
+    }
+
+    /// Stops tracking `publisher`, e.g. after it disconnected.
+    pub fn remove_publisher(&self, publisher: &EntityId) {
+        self.publishers.lock().unwrap().remove(publisher);
+    }
+
+    /// Forgets all publishers and counters.
+    pub fn reset(&self) {
+        self.publishers.lock().unwrap().clear();
+    }
+}
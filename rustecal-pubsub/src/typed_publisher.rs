@@ -1,4 +1,5 @@
 use crate::{
+    error::{PublishError, SerializationError},
     payload_writer::PayloadWriter,
     publisher::{Publisher, Timestamp},
     types::TopicId,
@@ -15,7 +16,28 @@ pub trait PublisherMessage {
     fn datatype() -> DataTypeInfo;
 
     /// Serializes the message into a shared, reference-counted byte buffer.
-    fn to_bytes(&self) -> Arc<[u8]>;
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`SerializationError`] if the message cannot be encoded.
+    fn to_bytes(&self) -> Result<Arc<[u8]>, SerializationError>;
+
+    /// Serializes the message for publishing with the given context.
+    ///
+    /// [`TypedPublisher`] calls this method. It defaults to
+    /// [`to_bytes`](Self::to_bytes); message types binding the payload to its
+    /// topic (e.g. signed envelopes) override it, and wrappers around other
+    /// messages forward it.
+    fn to_bytes_with(&self, _context: &SendContext<'_>) -> Result<Arc<[u8]>, SerializationError> {
+        self.to_bytes()
+    }
+}
+
+/// Context of a message being published, see [`PublisherMessage::to_bytes_with`].
+#[derive(Debug, Clone, Copy)]
+pub struct SendContext<'a> {
+    /// The topic the message is published on.
+    pub topic_name: &'a str,
 }
 
 /// A type-safe, high-level wrapper over an eCAL publisher for messages of type `T`.
@@ -24,6 +46,7 @@ pub trait PublisherMessage {
 /// (implementing [`PublisherMessage`]) are published.
 pub struct TypedPublisher<T: PublisherMessage> {
     publisher: Publisher,
+    topic_name: String,
     _phantom: PhantomData<T>,
 }
 
@@ -43,13 +66,14 @@ impl<T: PublisherMessage> TypedPublisher<T> {
 
         Ok(Self {
             publisher,
+            topic_name: topic_name.to_string(),
             _phantom: PhantomData,
         })
     }
 
     /// Sends a message of type `T` to all connected subscribers.
     ///
-    /// Serializes the message via [`PublisherMessage::to_bytes()`], and
+    /// Serializes the message via [`PublisherMessage::to_bytes_with()`], and
     /// specifies when to timestamp (auto or custom).
     ///
     /// # Arguments
@@ -59,10 +83,32 @@ impl<T: PublisherMessage> TypedPublisher<T> {
     ///
     /// # Returns
     ///
-    /// `true` on success, `false` on failure.
+    /// `true` on success, `false` if serialization or sending failed.
+    /// Use [`TypedPublisher::try_send`] to find out which.
     pub fn send(&self, message: &T, timestamp: Timestamp) -> bool {
-        let bytes = message.to_bytes();
-        self.publisher.send(&bytes, timestamp)
+        self.try_send(message, timestamp).is_ok()
+    }
+
+    /// Sends a message of type `T`, reporting why it could not be sent.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The typed message to send.
+    /// * `timestamp` - When to timestamp the message.
+    ///
+    /// # Errors
+    ///
+    /// - [`PublishError::Serialization`] if the message could not be serialized.
+    /// - [`PublishError::Send`] if eCAL failed to send the payload.
+    pub fn try_send(&self, message: &T, timestamp: Timestamp) -> Result<(), PublishError> {
+        let bytes = message.to_bytes_with(&SendContext {
+            topic_name: &self.topic_name,
+        })?;
+        if self.publisher.send(&bytes, timestamp) {
+            Ok(())
+        } else {
+            Err(PublishError::Send)
+        }
     }
 
     /// Performs a zero-copy send using a [`PayloadWriter`].
@@ -1,11 +1,15 @@
+use crate::latency::LatencyRecorder;
+use crate::sequence::SequenceTracker;
 use crate::subscriber::Subscriber;
 use crate::types::TopicId;
-use rustecal_core::types::DataTypeInfo;
+use rustecal_core::types::{DataTypeInfo, EntityId};
 use rustecal_sys::{eCAL_SDataTypeInformation, eCAL_SReceiveCallbackData, eCAL_STopicId};
 use std::{
+    any::Any,
     ffi::{CStr, c_void},
     marker::PhantomData,
     slice,
+    sync::Arc,
 };
 
 /// A trait for message types that can be deserialized by [`TypedSubscriber`].
@@ -27,6 +31,50 @@ pub trait SubscriberMessage<'a>: Sized {
     ///
     /// `Some(T)` on success, or `None` on failure.
     fn from_bytes(bytes: &'a [u8], data_type_info: &DataTypeInfo) -> Option<Self>;
+
+    /// Deserializes a message received with the given context.
+    ///
+    /// [`TypedSubscriber`] calls this method. It defaults to
+    /// [`from_bytes`](Self::from_bytes); message types that check the topic or
+    /// keep per-subscriber state (e.g. signed envelopes) override it, and
+    /// wrappers around other messages forward it.
+    fn from_bytes_with(
+        bytes: &'a [u8],
+        data_type_info: &DataTypeInfo,
+        _context: &ReceiveContext<'_>,
+    ) -> Option<Self> {
+        Self::from_bytes(bytes, data_type_info)
+    }
+}
+
+/// Context of a received message, see [`SubscriberMessage::from_bytes_with`].
+#[derive(Clone, Copy)]
+pub struct ReceiveContext<'a> {
+    /// The topic the message was received on.
+    pub topic_name: &'a str,
+    /// State attached with [`TypedSubscriber::set_receive_state`].
+    pub state: Option<&'a (dyn Any + Send + Sync)>,
+}
+
+impl<'a> ReceiveContext<'a> {
+    /// Creates a context without state.
+    pub fn new(topic_name: &'a str) -> Self {
+        Self {
+            topic_name,
+            state: None,
+        }
+    }
+
+    /// Returns the context with the given state.
+    pub fn with_state(mut self, state: &'a (dyn Any + Send + Sync)) -> Self {
+        self.state = Some(state);
+        self
+    }
+
+    /// Returns the attached state if it is of type `S`.
+    pub fn state<S: Any>(&self) -> Option<&'a S> {
+        self.state?.downcast_ref()
+    }
 }
 
 /// A received message, with payload and metadata.
@@ -43,21 +91,40 @@ pub struct Received<T> {
     pub timestamp: i64,
     /// The publisher's logical clock at send time.
     pub clock: i64,
+    /// The entity ID of the sending publisher.
+    pub publisher_id: EntityId,
 }
 
-/// Wrapper to store a boxed callback for `Received<T>`
+type Callback<T> = Arc<dyn Fn(Received<T>) + Send + Sync + 'static>;
+
+/// Wrapper to store the user callback for `Received<T>` and the attached statistics
 struct CallbackWrapper<'buf, T: SubscriberMessage<'buf>> {
-    callback: Box<dyn Fn(Received<T>) + Send + Sync + 'static>,
+    callback: Callback<T>,
+    tracker: Option<Arc<SequenceTracker>>,
+    latency: Option<Arc<LatencyRecorder>>,
+    state: Option<Arc<dyn Any + Send + Sync>>,
     _phantom: PhantomData<&'buf T>,
 }
 
+impl<'buf, T: SubscriberMessage<'buf>> Clone for CallbackWrapper<'buf, T> {
+    fn clone(&self) -> Self {
+        Self {
+            callback: self.callback.clone(),
+            tracker: self.tracker.clone(),
+            latency: self.latency.clone(),
+            state: self.state.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
 impl<'buf, T: SubscriberMessage<'buf>> CallbackWrapper<'buf, T> {
-    fn new<F>(f: F) -> Self
-    where
-        F: Fn(Received<T>) + Send + Sync + 'static,
-    {
+    fn new(callback: Callback<T>) -> Self {
         Self {
-            callback: Box::new(f),
+            callback,
+            tracker: None,
+            latency: None,
+            state: None,
             _phantom: PhantomData,
         }
     }
@@ -91,7 +158,7 @@ impl<'buf, T: SubscriberMessage<'buf>> TypedSubscriber<'buf, T> {
         let datatype = T::datatype();
 
         // dummy callback for construction
-        let boxed = Box::new(CallbackWrapper::new(|_| {}));
+        let boxed = Box::new(CallbackWrapper::new(Arc::new(|_| {})));
         let user_data = Box::into_raw(boxed);
 
         let subscriber = Subscriber::new(topic_name, datatype, trampoline::<'buf, T>)?;
@@ -107,18 +174,68 @@ impl<'buf, T: SubscriberMessage<'buf>> TypedSubscriber<'buf, T> {
     where
         F: Fn(Received<T>) + Send + Sync + 'static,
     {
-        // drop the old callback
-        unsafe {
-            let _ = Box::from_raw(self.user_data);
-        }
-        let boxed = Box::new(CallbackWrapper::new(callback));
-        self.user_data = Box::into_raw(boxed);
+        let mut wrapper = self.wrapper().clone();
+        wrapper.callback = Arc::new(callback);
+        self.register(wrapper);
+    }
+
+    /// Attaches a [`SequenceTracker`] that is fed the send clock of every
+    /// received message, before it is decoded.
+    ///
+    /// The tracker counts from the moment it is attached, also while no
+    /// callback is set and for messages that fail to decode. It may be shared
+    /// between subscribers to aggregate counters over several topics.
+    pub fn set_sequence_tracker(&mut self, tracker: Arc<SequenceTracker>) {
+        let mut wrapper = self.wrapper().clone();
+        wrapper.tracker = Some(tracker);
+        self.register(wrapper);
+    }
+
+    /// Returns the attached [`SequenceTracker`], if any.
+    pub fn sequence_tracker(&self) -> Option<Arc<SequenceTracker>> {
+        self.wrapper().tracker.clone()
+    }
+
+    /// Attaches a [`LatencyRecorder`] that measures the latency of every
+    /// received message against the eCAL time, before it is decoded.
+    ///
+    /// As with [`TypedSubscriber::set_sequence_tracker`], messages are
+    /// measured from the moment the recorder is attached.
+    pub fn set_latency_recorder(&mut self, recorder: Arc<LatencyRecorder>) {
+        let mut wrapper = self.wrapper().clone();
+        wrapper.latency = Some(recorder);
+        self.register(wrapper);
+    }
+
+    /// Returns the attached [`LatencyRecorder`], if any.
+    pub fn latency_recorder(&self) -> Option<Arc<LatencyRecorder>> {
+        self.wrapper().latency.clone()
+    }
+
+    /// Attaches state that is handed to [`SubscriberMessage::from_bytes_with`]
+    /// for every received message, e.g. the verification counters of signed
+    /// messages. Replaces previously attached state.
+    pub fn set_receive_state<S: Any + Send + Sync>(&mut self, state: Arc<S>) {
+        let mut wrapper = self.wrapper().clone();
+        wrapper.state = Some(state);
+        self.register(wrapper);
+    }
+
+    fn wrapper(&self) -> &CallbackWrapper<'buf, T> {
+        unsafe { &*self.user_data }
+    }
+
+    /// Registers `wrapper` with eCAL, then drops the previously registered one.
+    fn register(&mut self, wrapper: CallbackWrapper<'buf, T>) {
+        let old = self.user_data;
+        self.user_data = Box::into_raw(Box::new(wrapper));
         unsafe {
             rustecal_sys::eCAL_Subscriber_SetReceiveCallback(
                 self.subscriber.raw_handle(),
                 Some(trampoline::<'buf, T>),
                 self.user_data as *mut _,
             );
+            let _ = Box::from_raw(old);
         }
     }
 
@@ -172,8 +289,20 @@ extern "C" fn trampoline<'buf, T: SubscriberMessage<'buf> + 'buf>(
             return;
         }
 
-        // zero-copy view of the shared-memory payload
+        let cb_wrapper = &*(user_data as *const CallbackWrapper<'buf, T>);
         let rd = &*data;
+        let publisher_id = EntityId::from((*topic_id).topic_id);
+        let topic_name = CStr::from_ptr((*topic_id).topic_name)
+            .to_string_lossy()
+            .into_owned();
+        if let Some(latency) = &cb_wrapper.latency {
+            latency.record(&topic_name, &publisher_id, rd.send_timestamp);
+        }
+        if let Some(tracker) = &cb_wrapper.tracker {
+            tracker.track(&publisher_id, rd.send_clock);
+        }
+
+        // zero-copy view of the shared-memory payload
         let payload = slice::from_raw_parts(rd.buffer as *const u8, rd.buffer_size);
 
         // rebuild DataTypeInfo
@@ -192,11 +321,11 @@ extern "C" fn trampoline<'buf, T: SubscriberMessage<'buf> + 'buf>(
         };
 
         // direct-borrow deserialization
-        if let Some(decoded) = T::from_bytes(payload, &dt_info) {
-            let cb_wrapper = &*(user_data as *const CallbackWrapper<'buf, T>);
-            let topic_name = CStr::from_ptr((*topic_id).topic_name)
-                .to_string_lossy()
-                .into_owned();
+        let context = ReceiveContext {
+            topic_name: &topic_name,
+            state: cb_wrapper.state.as_deref(),
+        };
+        if let Some(decoded) = T::from_bytes_with(payload, &dt_info, &context) {
             let received = Received {
                 payload: decoded,
                 topic_name,
@@ -204,6 +333,7 @@ extern "C" fn trampoline<'buf, T: SubscriberMessage<'buf> + 'buf>(
                 type_name: type_name.clone(),
                 timestamp: rd.send_timestamp,
                 clock: rd.send_clock,
+                publisher_id,
             };
             cb_wrapper.call(received);
         }
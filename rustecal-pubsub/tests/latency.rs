@@ -0,0 +1,93 @@
+use rustecal_core::types::EntityId;
+use rustecal_pubsub::latency::LatencyHistogram;
+use rustecal_pubsub::{LatencyRecorder, LatencyStats};
+
+fn publisher(entity_id: u64) -> EntityId {
+    EntityId {
+        entity_id,
+        process_id: 42,
+        host_name: "host".to_string(),
+    }
+}
+
+#[test]
+fn empty_histogram_has_zero_stats() {
+    assert_eq!(LatencyHistogram::new().stats(), LatencyStats::default());
+}
+
+#[test]
+fn small_values_are_exact() {
+    let mut histogram = LatencyHistogram::new();
+    for latency in 1..=100 {
+        histogram.record(latency);
+    }
+
+    let stats = histogram.stats();
+    assert_eq!(stats.count, 100);
+    assert_eq!(stats.min, 1);
+    assert_eq!(stats.max, 100);
+    assert_eq!(stats.mean, 50.5);
+    assert_eq!(stats.p50, 50);
+    assert_eq!(stats.p99, 99);
+    assert_eq!(stats.p999, 100);
+}
+
+#[test]
+fn large_values_keep_relative_precision() {
+    let mut histogram = LatencyHistogram::new();
+    for latency in 1..=100_000u64 {
+        histogram.record(latency * 10);
+    }
+
+    for (percentile, exact) in [(50.0, 500_000u64), (99.0, 990_000), (99.9, 999_000)] {
+        let value = histogram.percentile(percentile);
+        assert!(value >= exact, "p{percentile}: {value} < {exact}");
+        assert!(
+            (value - exact) as f64 / exact as f64 <= 1.0 / 64.0,
+            "p{percentile}: {value} too far from {exact}"
+        );
+    }
+    assert_eq!(histogram.percentile(100.0), 1_000_000);
+
+    histogram.record(u64::MAX);
+    assert_eq!(histogram.stats().max, u64::MAX);
+    assert_eq!(histogram.percentile(100.0), u64::MAX);
+}
+
+#[test]
+fn recorder_groups_by_topic_and_publisher() {
+    let recorder = LatencyRecorder::new();
+    let pub_a = publisher(1);
+    let pub_b = publisher(2);
+
+    for latency in [10, 20, 30] {
+        recorder.record_latency("imu", &pub_a, latency);
+    }
+    recorder.record_latency("imu", &pub_b, 1000);
+    recorder.record_latency("camera", &pub_a, 5);
+
+    let a = recorder.publisher_stats("imu", &pub_a).unwrap();
+    assert_eq!((a.count, a.min, a.max, a.p50), (3, 10, 30, 20));
+
+    let imu = recorder.topic_stats("imu").unwrap();
+    assert_eq!((imu.count, imu.min, imu.max), (4, 10, 1000));
+    assert_eq!(imu.mean, 265.0);
+
+    assert!(recorder.topic_stats("lidar").is_none());
+    assert!(recorder.publisher_stats("camera", &pub_b).is_none());
+
+    let report = recorder.report();
+    let topics: Vec<_> = report
+        .iter()
+        .map(|entry| (entry.topic_name.as_str(), entry.publisher.entity_id))
+        .collect();
+    assert_eq!(topics, [("camera", 1), ("imu", 1), ("imu", 2)]);
+    assert!(
+        report[0]
+            .to_string()
+            .starts_with("latency camera [host:42:1] n=1 min=5us")
+    );
+
+    recorder.reset();
+    assert!(recorder.report().is_empty());
+}
@@ -0,0 +1,159 @@
+use rustecal_core::types::EntityId;
+use rustecal_pubsub::{SequenceEvent, SequenceStats, SequenceTracker};
+use std::sync::{Arc, Mutex};
+
+fn publisher(entity_id: u64) -> EntityId {
+    EntityId {
+        entity_id,
+        process_id: 42,
+        host_name: "host".to_string(),
+    }
+}
+
+#[test]
+fn in_order_messages_raise_no_events() {
+    let tracker = SequenceTracker::new();
+    let pub_a = publisher(1);
+
+    for clock in 5..15 {
+        assert_eq!(tracker.track(&pub_a, clock), None);
+    }
+    assert_eq!(
+        tracker.stats(),
+        SequenceStats {
+            received: 10,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn gap_is_reported_and_filled_by_late_message() {
+    let tracker = SequenceTracker::new();
+    let pub_a = publisher(1);
+
+    tracker.track(&pub_a, 1);
+    assert_eq!(
+        tracker.track(&pub_a, 4),
+        Some(SequenceEvent::Gap {
+            publisher: pub_a.clone(),
+            expected: 2,
+            received: 4,
+        })
+    );
+    assert_eq!(tracker.stats().lost, 2);
+
+    assert_eq!(
+        tracker.track(&pub_a, 3),
+        Some(SequenceEvent::Reordered {
+            publisher: pub_a.clone(),
+            clock: 3,
+        })
+    );
+    let stats = tracker.stats();
+    assert_eq!(stats.lost, 1);
+    assert_eq!(stats.reordered, 1);
+}
+
+#[test]
+fn duplicates_are_counted() {
+    let tracker = SequenceTracker::new();
+    let pub_a = publisher(1);
+
+    tracker.track(&pub_a, 10);
+    tracker.track(&pub_a, 11);
+    assert_eq!(
+        tracker.track(&pub_a, 10),
+        Some(SequenceEvent::Duplicate {
+            publisher: pub_a.clone(),
+            clock: 10,
+        })
+    );
+    assert_eq!(
+        tracker.track(&pub_a, 11),
+        Some(SequenceEvent::Duplicate {
+            publisher: pub_a.clone(),
+            clock: 11,
+        })
+    );
+
+    let stats = tracker.stats();
+    assert_eq!(stats.received, 4);
+    assert_eq!(stats.duplicates, 2);
+    assert_eq!(stats.lost, 0);
+}
+
+#[test]
+fn restart_resets_tracking() {
+    let tracker = SequenceTracker::new();
+    let pub_a = publisher(1);
+
+    for clock in 1..=5 {
+        tracker.track(&pub_a, clock);
+    }
+    // clock starts over, although it is within the window
+    assert_eq!(
+        tracker.track(&pub_a, 1),
+        Some(SequenceEvent::Restarted {
+            publisher: pub_a.clone(),
+            clock: 1,
+        })
+    );
+    assert_eq!(tracker.track(&pub_a, 2), None);
+
+    assert_eq!(tracker.stats().restarts, 1);
+}
+
+#[test]
+fn message_beyond_window_is_late() {
+    let tracker = SequenceTracker::new();
+    let pub_a = publisher(1);
+
+    for clock in 1..=200 {
+        tracker.track(&pub_a, clock);
+    }
+    // far behind the newest clock, but no restart
+    assert!(matches!(
+        tracker.track(&pub_a, 50),
+        Some(SequenceEvent::Late { clock: 50, .. })
+    ));
+    assert_eq!(tracker.track(&pub_a, 201), None);
+
+    let stats = tracker.stats();
+    assert_eq!(stats.late, 1);
+    assert_eq!(stats.restarts, 0);
+    assert_eq!(stats.duplicates, 0);
+    assert_eq!(stats.lost, 0);
+}
+
+#[test]
+fn publishers_are_tracked_separately() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = events.clone();
+    let tracker =
+        SequenceTracker::with_callback(move |event| sink.lock().unwrap().push(event.clone()));
+    let pub_a = publisher(1);
+    let pub_b = publisher(2);
+
+    tracker.track(&pub_a, 1);
+    tracker.track(&pub_b, 100);
+    tracker.track(&pub_a, 2);
+    tracker.track(&pub_b, 102);
+
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![SequenceEvent::Gap {
+            publisher: pub_b.clone(),
+            expected: 101,
+            received: 102,
+        }]
+    );
+    assert_eq!(tracker.publisher_stats(&pub_a).unwrap().lost, 0);
+    assert_eq!(tracker.publisher_stats(&pub_b).unwrap().lost, 1);
+    assert_eq!(tracker.stats().received, 4);
+
+    tracker.remove_publisher(&pub_b);
+    assert_eq!(tracker.publisher_stats(&pub_b), None);
+    tracker.reset();
+    assert_eq!(tracker.stats(), SequenceStats::default());
+}
@@ -0,0 +1,39 @@
+//! Argument parsing of the `rustecal-record` command line tool.
+
+/// Parses a byte count like `512M`.
+///
+/// Accepts the suffixes `K`, `M` and `G` for binary multiples.
+pub(crate) fn parse_size(text: &str) -> Result<u64, String> {
+    let (number, factor) = match text.char_indices().last() {
+        Some((i, 'K' | 'k')) => (&text[..i], 1 << 10),
+        Some((i, 'M' | 'm')) => (&text[..i], 1 << 20),
+        Some((i, 'G' | 'g')) => (&text[..i], 1 << 30),
+        _ => (text, 1),
+    };
+    let number = number
+        .parse::<u64>()
+        .map_err(|_| format!("invalid size '{text}'"))?;
+    number
+        .checked_mul(factor)
+        .ok_or_else(|| format!("size '{text}' is too large"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sizes() {
+        assert_eq!(parse_size("100"), Ok(100));
+        assert_eq!(parse_size("4k"), Ok(4 << 10));
+        assert_eq!(parse_size("512M"), Ok(512 << 20));
+        assert_eq!(parse_size("2G"), Ok(2 << 30));
+    }
+
+    #[test]
+    fn rejects_overflowing_sizes() {
+        assert!(parse_size("M").is_err());
+        assert!(parse_size(&format!("{}G", u64::MAX >> 20)).is_err());
+        assert!(parse_size(&format!("{}K", u64::MAX)).is_err());
+    }
+}
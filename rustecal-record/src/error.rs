@@ -0,0 +1,24 @@
+//! Error types of the recorder.
+
+use rustecal_core::error::RustecalError;
+use thiserror::Error;
+
+/// Errors returned while recording.
+#[derive(Debug, Error)]
+pub enum RecordError {
+    /// Creating, writing or flushing an output file failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The MCAP writer rejected a record.
+    #[error("MCAP error: {0}")]
+    Mcap(#[from] mcap::McapError),
+
+    /// A subscriber for the topic could not be created.
+    #[error("failed to subscribe to '{topic}': {reason}")]
+    Subscribe { topic: String, reason: String },
+
+    /// Topic discovery via eCAL monitoring failed.
+    #[error(transparent)]
+    Ecal(#[from] RustecalError),
+}
@@ -0,0 +1,20 @@
+//! # rustecal-record
+//!
+//! Records eCAL topics into [MCAP](https://mcap.dev) files, without the C++ eCAL recorder.
+//!
+//! Messages are stored undecoded, together with the data type announced by
+//! their publisher (see [`writer`] for the mapping onto MCAP schemas and
+//! channels), so recordings open in any MCAP tool and can be replayed into eCAL.
+//!
+//! The crate also builds the `rustecal-record` command line recorder.
+
+pub mod error;
+pub mod message;
+pub mod recorder;
+pub mod writer;
+
+pub use error::RecordError;
+pub use mcap::Compression;
+pub use message::RawMessage;
+pub use recorder::{Recorder, RecorderOptions, TopicSelection};
+pub use writer::{McapWriter, RecordedMessage, Split};
@@ -0,0 +1,141 @@
+//! Command line MCAP recorder for eCAL topics.
+
+mod cli;
+
+use cli::parse_size;
+use rustecal_core::{Ecal, EcalComponents};
+use rustecal_record::{Compression, Recorder, RecorderOptions, Split};
+use rustecal_tools_common::cli::parse_duration;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "\
+Usage: rustecal-record [OPTIONS] --output <FILE>
+
+Records eCAL topics into an MCAP file until interrupted with Ctrl+C.
+
+Options:
+  -o, --output <FILE>          MCAP file to write
+  -t, --topic <NAME>           Topic to record, may be repeated (default: all topics)
+      --split-size <SIZE>      Start a new file after SIZE bytes (suffixes K, M, G)
+      --split-duration <TIME>  Start a new file after TIME (suffixes ms, s, m, h)
+      --compression <ALGO>     Chunk compression: zstd (default), lz4 or none
+      --duration <TIME>        Stop recording after TIME
+  -h, --help                   Print this help";
+
+struct Args {
+    options: RecorderOptions,
+    duration: Option<Duration>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut output = None;
+    let mut topics = Vec::new();
+    let mut split = Split::default();
+    let mut compression = Some(Compression::Zstd);
+    let mut duration = None;
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value for {arg}"))
+        };
+        match arg.as_str() {
+            "-o" | "--output" => output = Some(value()?),
+            "-t" | "--topic" => topics.push(value()?),
+            "--split-size" => split.max_size = Some(parse_size(&value()?)?),
+            "--split-duration" => split.max_duration = Some(parse_duration(&value()?)?),
+            "--compression" => {
+                compression = match value()?.as_str() {
+                    "zstd" => Some(Compression::Zstd),
+                    "lz4" => Some(Compression::Lz4),
+                    "none" => None,
+                    other => return Err(format!("unknown compression '{other}'")),
+                }
+            }
+            "--duration" => duration = Some(parse_duration(&value()?)?),
+            "-h" | "--help" => return Ok(None),
+            other => return Err(format!("unexpected argument '{other}'")),
+        }
+    }
+
+    let output = output.ok_or("--output is required")?;
+    let mut options = RecorderOptions::new(output)
+        .with_split(split)
+        .with_compression(compression);
+    if !topics.is_empty() {
+        options = options.with_topics(topics);
+    }
+    Ok(Some(Args { options, duration }))
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(error) => {
+            eprintln!("error: {error}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if let Err(error) = Ecal::initialize(
+        Some("rustecal record"),
+        EcalComponents::DEFAULT | EcalComponents::MONITORING,
+        None,
+    ) {
+        eprintln!("error: eCAL initialization failed: {error}");
+        return ExitCode::FAILURE;
+    }
+
+    let result = record(args);
+    Ecal::finalize();
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn record(args: Args) -> Result<(), Box<dyn std::error::Error>> {
+    let running = Arc::new(AtomicBool::new(true));
+    {
+        let running = running.clone();
+        ctrlc::set_handler(move || running.store(false, Ordering::SeqCst))?;
+    }
+
+    let mut recorder = Recorder::start(args.options)?;
+    for topic in recorder.topics() {
+        println!("Recording topic     : {topic}");
+    }
+
+    let start = Instant::now();
+    while running.load(Ordering::SeqCst)
+        && Ecal::ok()
+        && args
+            .duration
+            .is_none_or(|duration| start.elapsed() < duration)
+    {
+        for topic in recorder.discover()? {
+            println!("Recording topic     : {topic}");
+        }
+        thread::sleep(Duration::from_millis(500));
+    }
+
+    let messages = recorder.message_count();
+    let files = recorder.stop()?;
+    println!("Recorded messages   : {messages}");
+    for file in files {
+        println!("Written file        : {}", file.display());
+    }
+    Ok(())
+}
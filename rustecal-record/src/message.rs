@@ -0,0 +1,33 @@
+//! Message type used to receive topics without decoding them.
+
+use rustecal_core::types::DataTypeInfo;
+use rustecal_pubsub::typed_subscriber::SubscriberMessage;
+
+/// An undecoded message together with the data type announced by its publisher.
+///
+/// Borrows the payload from the receive buffer, so it is only valid inside
+/// the subscriber callback.
+pub struct RawMessage<'a> {
+    /// The serialized payload.
+    pub data: &'a [u8],
+    /// Encoding, type name and descriptor of the publisher.
+    pub data_type: DataTypeInfo,
+}
+
+impl<'a> SubscriberMessage<'a> for RawMessage<'a> {
+    /// Subscribes without a data type, so any publisher on the topic matches.
+    fn datatype() -> DataTypeInfo {
+        DataTypeInfo {
+            encoding: String::new(),
+            type_name: String::new(),
+            descriptor: Vec::new(),
+        }
+    }
+
+    fn from_bytes(bytes: &'a [u8], data_type_info: &DataTypeInfo) -> Option<Self> {
+        Some(Self {
+            data: bytes,
+            data_type: data_type_info.clone(),
+        })
+    }
+}
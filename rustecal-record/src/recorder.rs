@@ -0,0 +1,230 @@
+//! Subscribing to eCAL topics and feeding them into an [`McapWriter`].
+
+use crate::error::RecordError;
+use crate::message::RawMessage;
+use crate::writer::{McapWriter, RecordedMessage, Split};
+use mcap::Compression;
+use rustecal_core::monitoring::Monitoring;
+use rustecal_core::time::Time;
+use rustecal_pubsub::TypedSubscriber;
+use rustecal_pubsub::typed_subscriber::Received;
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// Topics to record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TopicSelection {
+    /// Every topic with a publisher, as reported by eCAL monitoring.
+    ///
+    /// Requires eCAL to be initialized with `EcalComponents::MONITORING`.
+    All,
+    /// Only the given topics.
+    Topics(Vec<String>),
+}
+
+/// Configuration of a [`Recorder`].
+#[derive(Debug, Clone)]
+pub struct RecorderOptions {
+    /// Output file; numbered if splitting is enabled.
+    pub output: PathBuf,
+    pub topics: TopicSelection,
+    pub split: Split,
+    /// Chunk compression, zstd by default.
+    pub compression: Option<Compression>,
+}
+
+impl RecorderOptions {
+    /// Records all topics into `output`, zstd compressed and without splitting.
+    pub fn new(output: impl Into<PathBuf>) -> Self {
+        Self {
+            output: output.into(),
+            topics: TopicSelection::All,
+            split: Split::default(),
+            compression: Some(Compression::Zstd),
+        }
+    }
+
+    /// Records only the given topics.
+    pub fn with_topics<I, S>(mut self, topics: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.topics = TopicSelection::Topics(topics.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Sets the limits after which a new file is started.
+    pub fn with_split(mut self, split: Split) -> Self {
+        self.split = split;
+        self
+    }
+
+    /// Sets the chunk compression, `None` to disable it.
+    pub fn with_compression(mut self, compression: Option<Compression>) -> Self {
+        self.compression = compression;
+        self
+    }
+}
+
+/// State shared with the subscriber callbacks.
+struct State {
+    writer: Option<McapWriter>,
+    /// First write error; recording stops once set.
+    error: Option<RecordError>,
+    messages: u64,
+}
+
+/// Records eCAL topics into MCAP files.
+///
+/// ```ignore
+/// let mut recorder = Recorder::start(RecorderOptions::new("run.mcap"))?;
+/// while Ecal::ok() {
+///     recorder.discover()?;
+///     thread::sleep(Duration::from_secs(1));
+/// }
+/// let files = recorder.stop()?;
+/// ```
+///
+/// Messages are written from the eCAL receive threads as they arrive.
+pub struct Recorder {
+    state: Arc<Mutex<State>>,
+    selection: TopicSelection,
+    subscribers: HashMap<String, TypedSubscriber<'static, RawMessage<'static>>>,
+}
+
+impl Recorder {
+    /// Creates the first output file and subscribes to the selected topics.
+    ///
+    /// # Errors
+    /// Returns a [`RecordError`] if the file cannot be created, a subscriber
+    /// cannot be created, or topic discovery fails.
+    pub fn start(options: RecorderOptions) -> Result<Self, RecordError> {
+        let writer = McapWriter::create(&options.output, options.split, options.compression)?;
+        let mut recorder = Self {
+            state: Arc::new(Mutex::new(State {
+                writer: Some(writer),
+                error: None,
+                messages: 0,
+            })),
+            selection: options.topics,
+            subscribers: HashMap::new(),
+        };
+
+        match recorder.selection.clone() {
+            TopicSelection::All => {
+                recorder.discover()?;
+            }
+            TopicSelection::Topics(topics) => {
+                for topic in topics {
+                    recorder.subscribe(&topic)?;
+                }
+            }
+        }
+        Ok(recorder)
+    }
+
+    /// Subscribes to topics that appeared since the last call, if recording all
+    /// topics, and returns them.
+    ///
+    /// Call this periodically; it also reports a failed write.
+    ///
+    /// # Errors
+    /// Returns the first write error, or a [`RecordError`] if discovery or
+    /// subscribing fails.
+    pub fn discover(&mut self) -> Result<Vec<String>, RecordError> {
+        if let Some(error) = self.state.lock().unwrap().error.take() {
+            return Err(error);
+        }
+        if self.selection != TopicSelection::All {
+            return Ok(Vec::new());
+        }
+
+        let snapshot = Monitoring::get_snapshot()?;
+        let discovered: BTreeSet<String> = snapshot
+            .publishers
+            .into_iter()
+            .map(|publisher| publisher.topic_name)
+            .filter(|topic| !self.subscribers.contains_key(topic))
+            .collect();
+        for topic in &discovered {
+            self.subscribe(topic)?;
+        }
+        Ok(discovered.into_iter().collect())
+    }
+
+    /// Returns the recorded topics, sorted by name.
+    pub fn topics(&self) -> Vec<String> {
+        let mut topics: Vec<String> = self.subscribers.keys().cloned().collect();
+        topics.sort();
+        topics
+    }
+
+    /// Returns the number of messages written so far.
+    pub fn message_count(&self) -> u64 {
+        self.state.lock().unwrap().messages
+    }
+
+    /// Unsubscribes from all topics, finalizes the open file and returns all written files.
+    ///
+    /// # Errors
+    /// Returns the first write error, or a [`RecordError`] if finalizing fails.
+    pub fn stop(mut self) -> Result<Vec<PathBuf>, RecordError> {
+        self.subscribers.clear();
+
+        let mut state = self.state.lock().unwrap();
+        let files = match state.writer.take() {
+            Some(writer) => writer.finish(),
+            None => Ok(Vec::new()),
+        };
+        match state.error.take() {
+            Some(error) => Err(error),
+            None => files,
+        }
+    }
+
+    fn subscribe(&mut self, topic: &str) -> Result<(), RecordError> {
+        let mut subscriber =
+            TypedSubscriber::<RawMessage>::new(topic).map_err(|reason| RecordError::Subscribe {
+                topic: topic.to_string(),
+                reason,
+            })?;
+
+        let state = self.state.clone();
+        subscriber.set_callback(move |received: Received<RawMessage>| {
+            let receive_timestamp = Time::get_microseconds();
+            let mut state = state.lock().unwrap();
+            let state = &mut *state;
+            let Some(writer) = &mut state.writer else {
+                return;
+            };
+            if state.error.is_some() {
+                return;
+            }
+
+            let message = RecordedMessage {
+                topic_name: &received.topic_name,
+                data_type: &received.payload.data_type,
+                send_timestamp: received.timestamp,
+                receive_timestamp,
+                clock: received.clock,
+                data: received.payload.data,
+            };
+            match writer.write(&message) {
+                Ok(()) => state.messages += 1,
+                Err(error) => state.error = Some(error),
+            }
+        });
+
+        self.subscribers.insert(topic.to_string(), subscriber);
+        Ok(())
+    }
+}
+
+impl Drop for Recorder {
+    /// Stops all subscribers before the writer finalizes the open file.
+    fn drop(&mut self) {
+        self.subscribers.clear();
+    }
+}
@@ -0,0 +1,326 @@
+//! Writing eCAL messages into (split) MCAP files.
+//!
+//! Every eCAL topic becomes an MCAP channel, one per data type seen on it.
+//! The data type is mapped as follows:
+//!
+//! | eCAL                  | MCAP                                                      |
+//! |-----------------------|-----------------------------------------------------------|
+//! | `type_name`           | schema name                                               |
+//! | `encoding`            | schema and message encoding (`proto` → `protobuf`, ...)   |
+//! | `descriptor`          | schema data; channels without descriptor have no schema   |
+//!
+//! The original encoding and type name are also stored in the channel
+//! metadata under [`META_ENCODING`] and [`META_TYPE_NAME`], so the data type
+//! can be restored exactly on replay.
+
+use crate::error::RecordError;
+use mcap::records::MessageHeader;
+use mcap::{Compression, WriteOptions, Writer};
+use rustecal_core::types::DataTypeInfo;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+pub use rustecal_tools_common::mcap::{META_ENCODING, META_TYPE_NAME};
+
+/// Limits after which the recording continues in a new file.
+///
+/// If any limit is set, files are numbered: `out.mcap` becomes `out_000.mcap`,
+/// `out_001.mcap`, ...
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Split {
+    /// Maximum size of a file in bytes. Files end at the first chunk boundary
+    /// past this size, so they may be larger by up to one chunk.
+    pub max_size: Option<u64>,
+    /// Maximum receive time span covered by a file.
+    pub max_duration: Option<Duration>,
+}
+
+impl Split {
+    /// Splits files once they reach `bytes`.
+    pub fn by_size(bytes: u64) -> Self {
+        Self {
+            max_size: Some(bytes),
+            max_duration: None,
+        }
+    }
+
+    /// Splits files once they cover `duration`.
+    pub fn by_duration(duration: Duration) -> Self {
+        Self {
+            max_size: None,
+            max_duration: Some(duration),
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.max_size.is_some() || self.max_duration.is_some()
+    }
+}
+
+/// A received message to be recorded.
+#[derive(Debug, Clone, Copy)]
+pub struct RecordedMessage<'a> {
+    pub topic_name: &'a str,
+    pub data_type: &'a DataTypeInfo,
+    /// Publisher's send timestamp in microseconds, written as MCAP `publish_time`.
+    pub send_timestamp: i64,
+    /// eCAL time at receive in microseconds, written as MCAP `log_time`.
+    pub receive_timestamp: i64,
+    /// Publisher's send clock, written (truncated) as MCAP `sequence`.
+    pub clock: i64,
+    pub data: &'a [u8],
+}
+
+/// Writes messages into MCAP files, starting a new file according to [`Split`].
+pub struct McapWriter {
+    path: PathBuf,
+    split: Split,
+    compression: Option<Compression>,
+    current: Option<McapFile>,
+    files: Vec<PathBuf>,
+}
+
+impl McapWriter {
+    /// Creates the first output file.
+    ///
+    /// # Errors
+    /// Returns a [`RecordError`] if the file cannot be created.
+    pub fn create(
+        path: impl Into<PathBuf>,
+        split: Split,
+        compression: Option<Compression>,
+    ) -> Result<Self, RecordError> {
+        let mut writer = Self {
+            path: path.into(),
+            split,
+            compression,
+            current: None,
+            files: Vec::new(),
+        };
+        writer.open_next()?;
+        Ok(writer)
+    }
+
+    /// Appends a message, rolling over to a new file first if a split limit is reached.
+    ///
+    /// # Errors
+    /// Returns a [`RecordError`] if writing or rolling over fails.
+    pub fn write(&mut self, message: &RecordedMessage<'_>) -> Result<(), RecordError> {
+        let log_time = micros_to_nanos(message.receive_timestamp);
+        if self.is_full(log_time) {
+            self.close_current()?;
+            self.open_next()?;
+        }
+
+        let file = match &mut self.current {
+            Some(file) => file,
+            None => self.open_next()?,
+        };
+        let channel_id = file.channel(message.topic_name, message.data_type)?;
+        file.start_time.get_or_insert(log_time);
+        file.writer.write_to_known_channel(
+            &MessageHeader {
+                channel_id,
+                sequence: message.clock as u32,
+                log_time,
+                publish_time: micros_to_nanos(message.send_timestamp),
+            },
+            message.data,
+        )?;
+        Ok(())
+    }
+
+    /// Returns the files written so far, including the open one.
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// Finalizes the open file and returns all written files.
+    ///
+    /// # Errors
+    /// Returns a [`RecordError`] if the file summary cannot be written.
+    pub fn finish(mut self) -> Result<Vec<PathBuf>, RecordError> {
+        self.close_current()?;
+        Ok(std::mem::take(&mut self.files))
+    }
+
+    fn is_full(&self, log_time: u64) -> bool {
+        let Some(file) = &self.current else {
+            return false;
+        };
+        let too_large = self
+            .split
+            .max_size
+            .is_some_and(|max| file.size.load(Ordering::Relaxed) >= max);
+        let too_long = match (self.split.max_duration, file.start_time) {
+            (Some(max), Some(start)) => log_time.saturating_sub(start) >= max.as_nanos() as u64,
+            _ => false,
+        };
+        too_large || too_long
+    }
+
+    fn open_next(&mut self) -> Result<&mut McapFile, RecordError> {
+        let path = if self.split.is_enabled() {
+            numbered_path(&self.path, self.files.len())
+        } else {
+            self.path.clone()
+        };
+        let file = McapFile::create(&path, self.compression)?;
+        self.files.push(path);
+        Ok(self.current.insert(file))
+    }
+
+    fn close_current(&mut self) -> Result<(), RecordError> {
+        if let Some(mut file) = self.current.take() {
+            file.writer.finish()?;
+            file.writer.into_inner().inner.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for McapWriter {
+    fn drop(&mut self) {
+        let _ = self.close_current();
+    }
+}
+
+/// Returns `dir/stem_NNN.ext` for `dir/stem.ext`.
+fn numbered_path(path: &Path, index: usize) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "recording".to_string());
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "mcap".to_string());
+    path.with_file_name(format!("{stem}_{index:03}.{extension}"))
+}
+
+fn micros_to_nanos(micros: i64) -> u64 {
+    (micros.max(0) as u64).saturating_mul(1000)
+}
+
+/// Maps an eCAL encoding onto an MCAP schema encoding.
+///
+/// Suffixes like `+zstd` are ignored, as the descriptor describes the inner type.
+pub fn schema_encoding(encoding: &str) -> &str {
+    match encoding.split('+').next().unwrap_or_default() {
+        "proto" => "protobuf",
+        "flatb" => "flatbuffer",
+        "cdr" => "ros2msg",
+        "json" => "jsonschema",
+        other => other,
+    }
+}
+
+/// Maps an eCAL encoding onto an MCAP message encoding.
+pub fn message_encoding(encoding: &str) -> &str {
+    match encoding {
+        "proto" => "protobuf",
+        "flatb" => "flatbuffer",
+        other => other,
+    }
+}
+
+/// An open output file.
+struct McapFile {
+    writer: Writer<CountingWriter<BufWriter<File>>>,
+    size: Arc<AtomicU64>,
+    /// Log time of the first message.
+    start_time: Option<u64>,
+    /// Channels by topic, each with the encoding and type name it was created for.
+    channels: HashMap<String, Vec<(String, String, u16)>>,
+}
+
+impl McapFile {
+    fn create(path: &Path, compression: Option<Compression>) -> Result<Self, RecordError> {
+        let size = Arc::new(AtomicU64::new(0));
+        let sink = CountingWriter {
+            inner: BufWriter::new(File::create(path)?),
+            position: 0,
+            size: size.clone(),
+        };
+        let writer = WriteOptions::new()
+            .compression(compression)
+            .library(concat!("rustecal-record ", env!("CARGO_PKG_VERSION")))
+            .create(sink)?;
+        Ok(Self {
+            writer,
+            size,
+            start_time: None,
+            channels: HashMap::new(),
+        })
+    }
+
+    /// Returns the channel for `topic_name` and `data_type`, adding it (and its schema) if new.
+    fn channel(&mut self, topic_name: &str, data_type: &DataTypeInfo) -> Result<u16, RecordError> {
+        if let Some(channels) = self.channels.get(topic_name) {
+            let known = channels.iter().find(|(encoding, type_name, _)| {
+                *encoding == data_type.encoding && *type_name == data_type.type_name
+            });
+            if let Some((_, _, id)) = known {
+                return Ok(*id);
+            }
+        }
+
+        let schema_id = if data_type.descriptor.is_empty() {
+            0
+        } else {
+            self.writer.add_schema(
+                &data_type.type_name,
+                schema_encoding(&data_type.encoding),
+                &data_type.descriptor,
+            )?
+        };
+        let metadata = BTreeMap::from([
+            (META_ENCODING.to_string(), data_type.encoding.clone()),
+            (META_TYPE_NAME.to_string(), data_type.type_name.clone()),
+        ]);
+        let id = self.writer.add_channel(
+            schema_id,
+            topic_name,
+            message_encoding(&data_type.encoding),
+            &metadata,
+        )?;
+
+        self.channels
+            .entry(topic_name.to_string())
+            .or_default()
+            .push((data_type.encoding.clone(), data_type.type_name.clone(), id));
+        Ok(id)
+    }
+}
+
+/// Passes writes through and publishes the resulting file size.
+struct CountingWriter<W> {
+    inner: W,
+    position: u64,
+    size: Arc<AtomicU64>,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.position += written as u64;
+        self.size.fetch_max(self.position, Ordering::Relaxed);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W: Seek> Seek for CountingWriter<W> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.position = self.inner.seek(pos)?;
+        Ok(self.position)
+    }
+}
@@ -0,0 +1,147 @@
+use mcap::MessageStream;
+use rustecal_core::types::DataTypeInfo;
+use rustecal_record::writer::{META_ENCODING, META_TYPE_NAME};
+use rustecal_record::{Compression, McapWriter, RecordedMessage, Split};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Returns an empty directory for the output of one test.
+fn output_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rustecal-record-{}-{test}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn count_messages(path: &PathBuf) -> usize {
+    let bytes = std::fs::read(path).unwrap();
+    MessageStream::new(&bytes).unwrap().count()
+}
+
+fn proto_type() -> DataTypeInfo {
+    DataTypeInfo {
+        encoding: "proto".to_string(),
+        type_name: "pb.People.Person".to_string(),
+        descriptor: vec![1, 2, 3, 4],
+    }
+}
+
+fn string_type() -> DataTypeInfo {
+    DataTypeInfo {
+        encoding: "utf-8".to_string(),
+        type_name: "string".to_string(),
+        descriptor: Vec::new(),
+    }
+}
+
+fn message<'a>(
+    topic_name: &'a str,
+    data_type: &'a DataTypeInfo,
+    time: i64,
+    data: &'a [u8],
+) -> RecordedMessage<'a> {
+    RecordedMessage {
+        topic_name,
+        data_type,
+        send_timestamp: time,
+        receive_timestamp: time + 50,
+        clock: time / 1000,
+        data,
+    }
+}
+
+#[test]
+fn maps_data_types_onto_schemas_and_channels() {
+    let path = output_dir("mapping").join("out.mcap");
+    let person = proto_type();
+    let string = string_type();
+
+    let mut writer = McapWriter::create(&path, Split::default(), Some(Compression::Zstd)).unwrap();
+    writer
+        .write(&message("person", &person, 1_000, b"alice"))
+        .unwrap();
+    writer
+        .write(&message("hello", &string, 2_000, b"hello"))
+        .unwrap();
+    writer
+        .write(&message("person", &person, 3_000, b"bob"))
+        .unwrap();
+    assert_eq!(writer.finish().unwrap(), vec![path.clone()]);
+
+    let bytes = std::fs::read(&path).unwrap();
+    let messages: Vec<_> = MessageStream::new(&bytes)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(messages.len(), 3);
+
+    let first = &messages[0];
+    assert_eq!(first.channel.topic, "person");
+    assert_eq!(first.channel.message_encoding, "protobuf");
+    assert_eq!(first.channel.metadata[META_ENCODING], "proto");
+    assert_eq!(first.channel.metadata[META_TYPE_NAME], "pb.People.Person");
+    let schema = first.channel.schema.as_ref().unwrap();
+    assert_eq!(schema.name, "pb.People.Person");
+    assert_eq!(schema.encoding, "protobuf");
+    assert_eq!(&schema.data[..], &[1, 2, 3, 4]);
+    assert_eq!(first.publish_time, 1_000_000);
+    assert_eq!(first.log_time, 1_050_000);
+    assert_eq!(first.sequence, 1);
+    assert_eq!(&first.data[..], b"alice");
+
+    let second = &messages[1];
+    assert_eq!(second.channel.topic, "hello");
+    assert_eq!(second.channel.message_encoding, "utf-8");
+    assert_eq!(second.channel.metadata[META_TYPE_NAME], "string");
+    assert!(second.channel.schema.is_none());
+
+    assert_eq!(messages[2].channel.id, first.channel.id);
+}
+
+#[test]
+fn splits_by_duration() {
+    let path = output_dir("duration").join("out.mcap");
+    let string = string_type();
+
+    let split = Split::by_duration(Duration::from_secs(1));
+    let mut writer = McapWriter::create(&path, split, None).unwrap();
+    // one message every 300 ms over 3 seconds
+    for i in 0..10 {
+        writer
+            .write(&message("hello", &string, i * 300_000, b"hello"))
+            .unwrap();
+    }
+    let files = writer.finish().unwrap();
+
+    let names: Vec<_> = files
+        .iter()
+        .map(|file| file.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(names, ["out_000.mcap", "out_001.mcap", "out_002.mcap"]);
+    let counts: Vec<_> = files.iter().map(count_messages).collect();
+    assert_eq!(counts, [4, 4, 2]);
+}
+
+#[test]
+fn splits_by_size() {
+    let path = output_dir("size").join("out.mcap");
+    let string = string_type();
+    let payload = vec![b'x'; 10 * 1024];
+
+    let mut writer = McapWriter::create(&path, Split::by_size(1 << 20), None).unwrap();
+    for i in 0..300 {
+        writer
+            .write(&message("blob", &string, i * 1000, &payload))
+            .unwrap();
+    }
+    let files = writer.finish().unwrap();
+
+    assert!(files.len() >= 2, "expected a split, got {files:?}");
+    let total: usize = files.iter().map(count_messages).sum();
+    assert_eq!(total, 300);
+    // each file ends within one chunk past the limit
+    for file in &files {
+        let size = std::fs::metadata(file).unwrap().len();
+        assert!(size < 2 << 20, "{} is {size} bytes", file.display());
+    }
+}
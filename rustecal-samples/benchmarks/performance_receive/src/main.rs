@@ -1,6 +1,7 @@
 //! A performance benchmark subscriber in Rust, using the typed `BytesMessage` subscriber
 //! to demonstrate zero-copy payload support.
 
+use rustecal::pubsub::LatencyRecorder;
 use rustecal::pubsub::typed_subscriber::Received;
 use rustecal::{Ecal, EcalComponents, TypedSubscriber};
 use rustecal_types_bytes::BytesMessage;
@@ -29,11 +30,16 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let bytes = Arc::new(std::sync::atomic::AtomicU64::new(0));
     let start = Arc::new(Mutex::new(Instant::now()));
 
+    // end-to-end latency, measured on receive
+    let latency = Arc::new(LatencyRecorder::new());
+    subscriber.set_latency_recorder(latency.clone());
+
     // register the receive-callback
     {
         let msgs = Arc::clone(&msgs);
         let bytes = Arc::clone(&bytes);
         let start = Arc::clone(&start);
+        let latency = Arc::clone(&latency);
 
         subscriber.set_callback(move |msg: Received<BytesMessage>| {
             let buffer: &[u8] = msg.payload.data.as_ref();
@@ -74,7 +80,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 println!("Throughput   (GB/s) : {gbyte_s:.2}");
                 println!("Messages     (1/s)  : {msg_s:.0}");
                 println!("Latency      (µs)   : {latency_us:.2}");
+                if let Some(stats) = latency.topic_stats(&msg.topic_name) {
+                    println!(
+                        "E2E latency  (µs)   : min {} / p50 {} / p99 {} / p99.9 {} / max {}",
+                        stats.min, stats.p50, stats.p99, stats.p999, stats.max
+                    );
+                }
                 println!();
+                latency.reset();
 
                 // reset the timer
                 *start_lock = Instant::now();
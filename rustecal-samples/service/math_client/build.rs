@@ -1,7 +1,16 @@
+use rustecal_service_build::ServiceGenerator;
+
 fn main() {
     let protos = ["proto/math.proto"];
 
     let protos_inc = ["proto"];
 
-    prost_build::compile_protos(&protos, &protos_inc).unwrap();
+    // generate messages with reflection and the MathService server trait and client
+    let mut config = prost_build::Config::new();
+    config.service_generator(Box::new(ServiceGenerator::new()));
+
+    prost_reflect_build::Builder::new()
+        .descriptor_pool("crate::DESCRIPTOR_POOL")
+        .compile_protos_with_config(config, &protos, &protos_inc)
+        .unwrap();
 }
@@ -1,75 +1,64 @@
-use prost::Message;
-use rustecal::{CallState, ServiceClient, ServiceRequest};
 use rustecal::{Ecal, EcalComponents};
+use rustecal_types_protobuf::IsProtobufType;
 use std::thread;
 use std::time::Duration;
 
-// Add the protobuf compiled by prost
+// Add the protobuf messages and the MathService client compiled by prost
 mod math_pb {
     include!(concat!(env!("OUT_DIR"), "/_.rs"));
 }
-use math_pb::{SFloat, SFloatTuple};
+use math_pb::{MathServiceClient, SFloat, SFloatTuple};
+
+use prost_reflect::DescriptorPool;
+use std::sync::LazyLock;
+
+static DESCRIPTOR_POOL: LazyLock<DescriptorPool> = LazyLock::new(|| {
+    DescriptorPool::decode(
+        include_bytes!(concat!(env!("OUT_DIR"), "/file_descriptor_set.bin")).as_ref(),
+    )
+    .unwrap()
+});
+
+impl IsProtobufType for SFloatTuple {}
+impl IsProtobufType for SFloat {}
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // initialize eCAL
     Ecal::initialize(Some("math client rust"), EcalComponents::DEFAULT, None)
         .expect("eCAL initialization failed");
 
-    let client = ServiceClient::new("MathService")?;
+    let client = MathServiceClient::new()?;
 
     // wait until connected
-    while client.get_client_instances().is_empty() {
+    while !client.client().client().is_connected() {
         println!("Waiting for a service ..");
         thread::sleep(Duration::from_secs(1));
     }
 
-    let methods = ["Add", "Multiply", "Divide"];
     let mut i = 0;
 
     while Ecal::ok() {
-        let method_name = methods[i % methods.len()];
-        i += 1;
-
-        let payload_pb = SFloatTuple {
+        let request = SFloatTuple {
             inp1: i as f64,
             inp2: (i + 1) as f64,
         };
 
-        let request = ServiceRequest {
-            payload: payload_pb.encode_to_vec(),
+        let (method_name, response) = match i % 3 {
+            0 => ("Add", client.add(request, Some(1000))),
+            1 => ("Multiply", client.multiply(request, Some(1000))),
+            _ => ("Divide", client.divide(request, Some(1000))),
         };
+        i += 1;
 
-        for instance in client.get_client_instances() {
-            let response = instance.call(method_name, request.clone(), Some(1000));
-
-            println!();
-            println!(
-                "Method '{}' called with message: {:?}",
-                method_name, payload_pb
-            );
+        println!();
+        println!("Method '{method_name}' called with message: {request:?}");
 
-            match response {
-                Some(res) => match CallState::from(res.success as i32) {
-                    CallState::Executed => {
-                        let response_data = SFloat::decode(&res.payload[..])?;
-                        println!(
-                            "Received response: {:?} from service id {:?}",
-                            response_data, res.server_id.service_id.entity_id
-                        );
-                    }
-                    CallState::Failed => {
-                        println!(
-                            "Received error: {} from service id {:?}",
-                            res.error_msg.unwrap_or_else(|| "Unknown".into()),
-                            res.server_id.service_id.entity_id
-                        );
-                    }
-                    _ => {}
-                },
-                None => {
-                    println!("Method blocking call failed ..");
-                }
-            }
+        match response {
+            Ok(res) => println!(
+                "Received response: {:?} from service id {:?}",
+                res.payload, res.server_id.service_id.entity_id
+            ),
+            Err(error) => println!("Received error: {error}"),
         }
 
         thread::sleep(Duration::from_secs(1));
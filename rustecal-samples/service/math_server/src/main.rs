@@ -1,113 +1,65 @@
-use prost::Message;
-use rustecal::{Ecal, EcalComponents};
-use rustecal::{MethodInfo, ServiceServer};
+use rustecal::{Ecal, EcalComponents, MethodError};
+use rustecal_types_protobuf::IsProtobufType;
 
-// Add the protobuf compiled by prost
+// Add the protobuf messages and the MathService server trait compiled by prost
 mod math_pb {
     include!(concat!(env!("OUT_DIR"), "/_.rs"));
 }
-use math_pb::{SFloat, SFloatTuple};
+use math_pb::{MathService, MathServiceServer, SFloat, SFloatTuple};
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // initialize eCAL
-    Ecal::initialize(Some("math server rust"), EcalComponents::DEFAULT, None)
-        .expect("eCAL initialization failed");
+use prost_reflect::DescriptorPool;
+use std::sync::LazyLock;
 
-    // create the service server named "MathService"
-    let mut server = ServiceServer::new("MathService")?;
+static DESCRIPTOR_POOL: LazyLock<DescriptorPool> = LazyLock::new(|| {
+    DescriptorPool::decode(
+        include_bytes!(concat!(env!("OUT_DIR"), "/file_descriptor_set.bin")).as_ref(),
+    )
+    .unwrap()
+});
 
-    // register "Add" method from protobuf rpc
-    server.add_method(
-        "Add",
-        Box::new(|info: MethodInfo, req: &[u8]| {
-            // Decode serialized protobuf request
-            let req_pb = SFloatTuple::decode(req);
+impl IsProtobufType for SFloatTuple {}
+impl IsProtobufType for SFloat {}
 
-            match req_pb {
-                Ok(pb) => {
-                    println!(
-                        "Received request for MathService in Rust: {}",
-                        info.method_name
-                    );
-                    println!("Input1 : {}", pb.inp1);
-                    println!("Input2 : {}", pb.inp2);
-                    println!();
+/// Prints the request and answers it with `op` applied to both inputs.
+fn calculate(method: &str, request: SFloatTuple, op: fn(f64, f64) -> f64) -> SFloat {
+    println!("Received request for MathService in Rust: {method}");
+    println!("Input1 : {}", request.inp1);
+    println!("Input2 : {}", request.inp2);
+    println!();
 
-                    let result = SFloat {
-                        out: pb.inp1 + pb.inp2,
-                    };
-                    // Serialize protobuf response
-                    result.encode_to_vec()
-                }
-                _ => {
-                    println!("Unable to decode protobuf");
-                    vec![]
-                }
-            }
-        }),
-    )?;
+    SFloat {
+        out: op(request.inp1, request.inp2),
+    }
+}
 
-    // register "Multiply" method from protobuf rpc
-    server.add_method(
-        "Multiply",
-        Box::new(|info: MethodInfo, req: &[u8]| {
-            // Decode serialized protobuf request
-            let req_pb = SFloatTuple::decode(req);
+struct Math;
 
-            match req_pb {
-                Ok(pb) => {
-                    println!(
-                        "Received request for MathService in Rust: {}",
-                        info.method_name
-                    );
-                    println!("Input1 : {}", pb.inp1);
-                    println!("Input2 : {}", pb.inp2);
-                    println!();
+impl MathService for Math {
+    fn add(&self, request: SFloatTuple) -> Result<SFloat, MethodError> {
+        Ok(calculate("Add", request, |a, b| a + b))
+    }
 
-                    let result = SFloat {
-                        out: pb.inp1 * pb.inp2,
-                    };
-                    // Serialize protobuf response
-                    result.encode_to_vec()
-                }
-                _ => {
-                    println!("Unable to decode protobuf");
-                    vec![]
-                }
-            }
-        }),
-    )?;
+    fn multiply(&self, request: SFloatTuple) -> Result<SFloat, MethodError> {
+        Ok(calculate("Multiply", request, |a, b| a * b))
+    }
 
-    // register "Divide" method from protobuf rpc
-    server.add_method(
-        "Divide",
-        Box::new(|info: MethodInfo, req: &[u8]| {
-            // Decode serialized protobuf request
-            let req_pb = SFloatTuple::decode(req);
+    fn divide(&self, request: SFloatTuple) -> Result<SFloat, MethodError> {
+        // reported to the client as failed call
+        if request.inp2 == 0.0 {
+            return Err(MethodError::new("division by zero"));
+        }
+        Ok(calculate("Divide", request, |a, b| a / b))
+    }
+}
 
-            match req_pb {
-                Ok(pb) => {
-                    println!(
-                        "Received request for MathService in Rust: {}",
-                        info.method_name
-                    );
-                    println!("Input1 : {}", pb.inp1);
-                    println!("Input2 : {}", pb.inp2);
-                    println!();
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // initialize eCAL
+    Ecal::initialize(Some("math server rust"), EcalComponents::DEFAULT, None)
+        .expect("eCAL initialization failed");
 
-                    let result = SFloat {
-                        out: pb.inp1 / pb.inp2,
-                    };
-                    // Serialize protobuf response
-                    result.encode_to_vec()
-                }
-                _ => {
-                    println!("Unable to decode protobuf");
-                    vec![]
-                }
-            }
-        }),
-    )?;
+    // serve all methods of the protobuf "MathService"; requests that cannot be
+    // decoded are answered with an error automatically
+    let _server = MathServiceServer::new(Math)?;
 
     println!("Rust math service running. Press Ctrl+C to exit.");
 
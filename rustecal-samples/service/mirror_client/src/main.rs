@@ -11,7 +11,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let client = ServiceClient::new("mirror")?;
 
     // wait until connected
-    while client.get_client_instances().is_empty() {
+    while !client.is_connected() {
         println!("Waiting for a service ..");
         thread::sleep(Duration::from_secs(1));
     }
@@ -27,35 +27,34 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             payload: b"stressed".to_vec(),
         };
 
-        for instance in client.get_client_instances() {
-            let response = instance.call(method_name, request.clone(), Some(1000));
+        println!();
+        println!("Method '{method_name}' called with message: stressed");
 
-            println!();
-            println!("Method '{method_name}' called with message: stressed");
-
-            match response {
-                Some(res) => match CallState::from(res.success as i32) {
-                    CallState::Executed => {
-                        let text = String::from_utf8_lossy(&res.payload);
-                        println!(
-                            "Received response: {} from service id {:?}",
-                            text, res.server_id.service_id.entity_id
-                        );
-                    }
-                    CallState::Failed => {
-                        println!(
-                            "Received error: {} from service id {:?}",
-                            res.error_msg.unwrap_or_else(|| "Unknown".into()),
-                            res.server_id.service_id.entity_id
-                        );
-                    }
-                    _ => {}
-                },
-                None => {
-                    println!("Method blocking call failed ..");
+        // responses arrive one by one, as soon as each server instance answered
+        let mut answered = false;
+        for res in client.call_iter(method_name, request, Some(1000)) {
+            answered = true;
+            match res.call_state {
+                CallState::Executed => {
+                    let text = String::from_utf8_lossy(&res.payload);
+                    println!(
+                        "Received response: {} from service id {:?}",
+                        text, res.server_id.service_id.entity_id
+                    );
+                }
+                state => {
+                    println!(
+                        "Received error: {} ({:?}) from service id {:?}",
+                        res.error_msg.unwrap_or_else(|| "Unknown".into()),
+                        state,
+                        res.server_id.service_id.entity_id
+                    );
                 }
             }
         }
+        if !answered {
+            println!("Method blocking call failed ..");
+        }
 
         thread::sleep(Duration::from_secs(1));
     }
@@ -45,14 +45,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                             );
                         } else {
                             println!(
-                                "Received error: {} from service id {:?}",
+                                "Received error ({:?}): {} from {}",
+                                res.call_state,
                                 res.error_msg.unwrap_or_else(|| "Unknown".into()),
-                                res.server_id.service_id.entity_id
+                                res.server_id
                             );
                         }
                     }
                     None => {
-                        println!("Call failed or timed out.");
+                        println!("No response from instance.");
                     }
                 }
             }
@@ -7,7 +7,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .expect("eCAL initialization failed");
 
     // create the service server named "mirror"
-    let mut server = ServiceServer::new("mirror")?;
+    let server = ServiceServer::new("mirror")?;
 
     // register "echo" method: respond with request unchanged
     server.add_method(
@@ -0,0 +1,273 @@
+//! # rustecal-service-build
+//!
+//! Build-time code generation for protobuf `service` definitions.
+//!
+//! [`ServiceGenerator`] plugs into `prost-build` and emits, per protobuf
+//! service `Foo`:
+//!
+//! - a trait `Foo` with one method per rpc, to be implemented by the server;
+//!   an `Err(MethodError)` is returned to rustecal callers as failed call,
+//!   while C++ or Python callers receive its marked payload (see `MethodError`),
+//! - a `FooServer` serving an implementation of `Foo` on top of
+//!   `TypedServiceServer`, registering request and response types of all
+//!   methods with eCAL,
+//! - a `FooClient` with one typed method per rpc on top of `TypedServiceClient`.
+//!
+//! Both use the full protobuf name of the service (`package.Foo`) as eCAL
+//! service name, like the eCAL C++ API. Messages are wrapped in
+//! `ProtobufMessage`, so they have to implement `IsProtobufType` and
+//! `ReflectMessage`, the latter generated by `prost-reflect-build`:
+//!
+//! ```ignore
+//! // build.rs
+//! let mut config = prost_build::Config::new();
+//! config.service_generator(Box::new(rustecal_service_build::ServiceGenerator::new()));
+//! prost_reflect_build::Builder::new()
+//!     .descriptor_pool("crate::DESCRIPTOR_POOL")
+//!     .compile_protos_with_config(config, &["proto/math.proto"], &["proto"])
+//!     .unwrap();
+//! ```
+//!
+//! Streaming rpcs are not supported by eCAL and are skipped. The generated
+//! items allow `dead_code`, as most users only need either server or client.
+
+use prost_build::{Method, Service};
+use std::fmt::Write;
+
+/// A `prost_build::ServiceGenerator` emitting eCAL service servers and clients.
+#[derive(Debug, Clone)]
+pub struct ServiceGenerator {
+    rustecal_path: String,
+    protobuf_path: String,
+}
+
+impl Default for ServiceGenerator {
+    fn default() -> Self {
+        Self {
+            rustecal_path: "::rustecal".to_string(),
+            protobuf_path: "::rustecal_types_protobuf".to_string(),
+        }
+    }
+}
+
+impl ServiceGenerator {
+    /// Creates a generator referring to the `rustecal` and `rustecal-types-protobuf` crates.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the path the generated code uses for the `rustecal` crate.
+    ///
+    /// Use `"::rustecal_service"` to depend on `rustecal-service` directly.
+    pub fn with_rustecal_path(mut self, path: impl Into<String>) -> Self {
+        self.rustecal_path = path.into();
+        self
+    }
+
+    /// Sets the path the generated code uses for the `rustecal-types-protobuf` crate.
+    pub fn with_protobuf_path(mut self, path: impl Into<String>) -> Self {
+        self.protobuf_path = path.into();
+        self
+    }
+
+    fn generate_trait(&self, service: &Service, methods: &[&Method], buf: &mut String) {
+        service.comments.append_with_indent(0, buf);
+        if service.comments.leading.is_empty() {
+            writeln!(
+                buf,
+                "/// Server side of the `{}` service.",
+                service.proto_name
+            )
+            .unwrap();
+        }
+        writeln!(buf, "#[allow(dead_code)]").unwrap();
+        writeln!(buf, "pub trait {}: Send + Sync + 'static {{", service.name).unwrap();
+        for method in methods {
+            method.comments.append_with_indent(1, buf);
+            writeln!(
+                buf,
+                "    fn {}(&self, request: {}) -> ::std::result::Result<{}, {}::MethodError>;",
+                method.name, method.input_type, method.output_type, self.rustecal_path
+            )
+            .unwrap();
+        }
+        writeln!(buf, "}}").unwrap();
+    }
+
+    fn generate_server(&self, service: &Service, methods: &[&Method], buf: &mut String) {
+        let Self {
+            rustecal_path: ecal,
+            protobuf_path: pb,
+        } = self;
+        let name = &service.name;
+
+        writeln!(
+            buf,
+            r#"
+/// Serves an implementation of [`{name}`] as eCAL service.
+#[allow(dead_code)]
+pub struct {name}Server {{
+    server: {ecal}::TypedServiceServer,
+}}
+
+#[allow(dead_code)]
+impl {name}Server {{
+    /// The eCAL service name, the full protobuf name of the service.
+    pub const SERVICE_NAME: &'static str = "{full_name}";
+
+    /// Serves `service` under [`Self::SERVICE_NAME`].
+    pub fn new<T: {name}>(service: T) -> ::std::result::Result<Self, ::std::string::String> {{
+        Self::with_name(Self::SERVICE_NAME, service)
+    }}
+
+    /// Serves `service` under a custom service name.
+    pub fn with_name<T: {name}>(
+        service_name: &str,
+        service: T,
+    ) -> ::std::result::Result<Self, ::std::string::String> {{
+        let service = ::std::sync::Arc::new(service);
+        let server = {ecal}::TypedServiceServer::new(service_name)?;"#,
+            full_name = full_name(service),
+        )
+        .unwrap();
+        for method in methods {
+            writeln!(
+                buf,
+                r#"        {{
+            let service = service.clone();
+            server.add_fallible_method(
+                "{proto_name}",
+                move |_info, request: {pb}::ProtobufMessage<{input}>| {{
+                    let response = service.{method}(::std::sync::Arc::unwrap_or_clone(request.data))?;
+                    Ok({pb}::ProtobufMessage {{
+                        data: ::std::sync::Arc::new(response),
+                    }})
+                }},
+            )?;
+        }}"#,
+                proto_name = method.proto_name,
+                input = method.input_type,
+                method = method.name,
+            )
+            .unwrap();
+        }
+        writeln!(
+            buf,
+            r#"        Ok(Self {{ server }})
+    }}
+
+    /// Returns the underlying typed server.
+    pub fn server(&mut self) -> &mut {ecal}::TypedServiceServer {{
+        &mut self.server
+    }}
+}}"#
+        )
+        .unwrap();
+    }
+
+    fn generate_client(&self, service: &Service, methods: &[&Method], buf: &mut String) {
+        let Self {
+            rustecal_path: ecal,
+            protobuf_path: pb,
+        } = self;
+        let name = &service.name;
+
+        writeln!(
+            buf,
+            r#"
+/// Typed client of the `{proto_name}` eCAL service.
+#[allow(dead_code)]
+pub struct {name}Client {{
+    client: {ecal}::TypedServiceClient,
+}}
+
+#[allow(dead_code)]
+impl {name}Client {{
+    /// The eCAL service name, the full protobuf name of the service.
+    pub const SERVICE_NAME: &'static str = "{full_name}";
+
+    /// Creates a client for [`Self::SERVICE_NAME`].
+    pub fn new() -> ::std::result::Result<Self, ::std::string::String> {{
+        Self::with_name(Self::SERVICE_NAME)
+    }}
+
+    /// Creates a client for a custom service name.
+    pub fn with_name(service_name: &str) -> ::std::result::Result<Self, ::std::string::String> {{
+        Ok(Self {{
+            client: {ecal}::TypedServiceClient::new(service_name)?,
+        }})
+    }}"#,
+            proto_name = service.proto_name,
+            full_name = full_name(service),
+        )
+        .unwrap();
+        for method in methods {
+            writeln!(buf).unwrap();
+            method.comments.append_with_indent(1, buf);
+            writeln!(
+                buf,
+                r#"    pub fn {method}(
+        &self,
+        request: {input},
+        timeout_ms: ::std::option::Option<i32>,
+    ) -> ::std::result::Result<{ecal}::TypedResponse<{output}>, {ecal}::CallError> {{
+        let request = {pb}::ProtobufMessage {{
+            data: ::std::sync::Arc::new(request),
+        }};
+        let response = self
+            .client
+            .call::<_, {pb}::ProtobufMessage<{output}>>("{proto_name}", &request, timeout_ms)?;
+        Ok({ecal}::TypedResponse {{
+            payload: ::std::sync::Arc::unwrap_or_clone(response.payload.data),
+            server_id: response.server_id,
+            ret_state: response.ret_state,
+        }})
+    }}"#,
+                method = method.name,
+                input = method.input_type,
+                output = method.output_type,
+                proto_name = method.proto_name,
+            )
+            .unwrap();
+        }
+        writeln!(
+            buf,
+            r#"
+    /// Returns the underlying typed client.
+    pub fn client(&self) -> &{ecal}::TypedServiceClient {{
+        &self.client
+    }}
+}}"#
+        )
+        .unwrap();
+    }
+}
+
+impl prost_build::ServiceGenerator for ServiceGenerator {
+    fn generate(&mut self, service: Service, buf: &mut String) {
+        let (methods, streaming): (Vec<&Method>, Vec<&Method>) = service
+            .methods
+            .iter()
+            .partition(|method| !method.client_streaming && !method.server_streaming);
+        for method in streaming {
+            println!(
+                "cargo:warning=skipping streaming rpc {}.{}, not supported by eCAL services",
+                full_name(&service),
+                method.proto_name
+            );
+        }
+
+        self.generate_trait(&service, &methods, buf);
+        self.generate_server(&service, &methods, buf);
+        self.generate_client(&service, &methods, buf);
+    }
+}
+
+/// Returns the protobuf name of `service` including its package.
+fn full_name(service: &Service) -> String {
+    if service.package.is_empty() {
+        service.proto_name.clone()
+    } else {
+        format!("{}.{}", service.package, service.proto_name)
+    }
+}
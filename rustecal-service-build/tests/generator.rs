@@ -0,0 +1,93 @@
+use prost_build::{Comments, Method, Service, ServiceGenerator as _};
+use rustecal_service_build::ServiceGenerator;
+
+fn method(name: &str, proto_name: &str, streaming: bool) -> Method {
+    Method {
+        name: name.to_string(),
+        proto_name: proto_name.to_string(),
+        comments: Comments::default(),
+        input_type: "SFloatTuple".to_string(),
+        output_type: "SFloat".to_string(),
+        input_proto_type: ".math.SFloatTuple".to_string(),
+        output_proto_type: ".math.SFloat".to_string(),
+        options: Default::default(),
+        client_streaming: false,
+        server_streaming: streaming,
+    }
+}
+
+fn service(package: &str, methods: Vec<Method>) -> Service {
+    Service {
+        name: "MathService".to_string(),
+        proto_name: "MathService".to_string(),
+        package: package.to_string(),
+        comments: Comments::default(),
+        methods,
+        options: Default::default(),
+    }
+}
+
+fn generate(generator: &mut ServiceGenerator, service: Service) -> String {
+    let mut buf = String::new();
+    generator.generate(service, &mut buf);
+    buf
+}
+
+#[test]
+fn generates_trait_server_and_client() {
+    let code = generate(
+        &mut ServiceGenerator::new(),
+        service(
+            "math",
+            vec![
+                method("add", "Add", false),
+                method("multiply", "Multiply", false),
+            ],
+        ),
+    );
+
+    assert!(code.contains("pub trait MathService: Send + Sync + 'static {"));
+    assert!(code.contains(
+        "fn add(&self, request: SFloatTuple) -> ::std::result::Result<SFloat, ::rustecal::MethodError>;"
+    ));
+    assert!(code.contains("fn multiply(&self, request: SFloatTuple)"));
+
+    assert!(code.contains("pub struct MathServiceServer {"));
+    assert!(code.contains("pub struct MathServiceClient {"));
+    assert!(code.contains(r#"pub const SERVICE_NAME: &'static str = "math.MathService";"#));
+    assert!(code.contains(r#""Add","#));
+    assert!(code.contains("::rustecal::TypedServiceServer::new(service_name)"));
+    assert!(code.contains("::rustecal_types_protobuf::ProtobufMessage<SFloatTuple>"));
+    assert!(code.contains("pub fn multiply("));
+}
+
+#[test]
+fn uses_configured_paths_and_plain_name_without_package() {
+    let mut generator = ServiceGenerator::new()
+        .with_rustecal_path("::rustecal_service")
+        .with_protobuf_path("crate::pb");
+    let code = generate(
+        &mut generator,
+        service("", vec![method("add", "Add", false)]),
+    );
+
+    assert!(code.contains(r#"pub const SERVICE_NAME: &'static str = "MathService";"#));
+    assert!(code.contains("::rustecal_service::TypedServiceClient::new(service_name)"));
+    assert!(code.contains("crate::pb::ProtobufMessage<SFloat>"));
+    assert!(!code.contains("::rustecal::"));
+}
+
+#[test]
+fn skips_streaming_methods() {
+    let code = generate(
+        &mut ServiceGenerator::new(),
+        service(
+            "math",
+            vec![method("add", "Add", false), method("sum", "Sum", true)],
+        ),
+    );
+
+    assert!(code.contains("fn add("));
+    assert!(!code.contains("fn sum("));
+    assert!(!code.contains(r#""Sum""#));
+}
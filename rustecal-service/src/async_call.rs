@@ -0,0 +1,359 @@
+//! Futures for asynchronous service calls.
+//!
+//! eCAL invokes a response callback once per called server instance. The
+//! futures count the instances at call time and complete when all of them
+//! answered (or, for [`CallFuture`], when the first one answered).
+//! [`ResponseIter`] yields the responses of such a call as they arrive.
+//!
+//! The callback only receives an id as user data and looks the call up in a
+//! global registry, so responses arriving after a future was dropped or
+//! cancelled are discarded safely.
+//!
+//! Calls with a timeout are finished by a timer thread, so a server that
+//! disappears before answering does not leave the future pending forever.
+
+use crate::response::ServiceResponse;
+use rustecal_sys::*;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::future::Future;
+use std::os::raw::c_void;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, LazyLock, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use std::time::{Duration, Instant};
+
+static PENDING: LazyLock<Mutex<HashMap<usize, Arc<PendingCall>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+
+/// Deadlines of calls with a timeout, served by one timer thread.
+static DEADLINES: LazyLock<Arc<Deadlines>> = LazyLock::new(|| {
+    let deadlines = Arc::new(Deadlines {
+        queue: Mutex::new(BinaryHeap::new()),
+        changed: Condvar::new(),
+    });
+    let timer = deadlines.clone();
+    thread::Builder::new()
+        .name("rustecal-service-timer".into())
+        .spawn(move || timer.run())
+        .expect("failed to spawn service call timer thread");
+    deadlines
+});
+
+struct Deadlines {
+    queue: Mutex<BinaryHeap<Reverse<(Instant, usize)>>>,
+    changed: Condvar,
+}
+
+impl Deadlines {
+    fn schedule(&self, id: usize, deadline: Instant) {
+        self.queue.lock().unwrap().push(Reverse((deadline, id)));
+        self.changed.notify_one();
+    }
+
+    /// Finishes each call when its deadline passed; calls that completed
+    /// before are no longer registered and are skipped.
+    fn run(&self) {
+        let mut queue = self.queue.lock().unwrap();
+        loop {
+            let now = Instant::now();
+            match queue.peek() {
+                Some(&Reverse((deadline, id))) if deadline <= now => {
+                    queue.pop();
+                    drop(queue);
+                    finish(id);
+                    queue = self.queue.lock().unwrap();
+                }
+                Some(&Reverse((deadline, _))) => {
+                    queue = self.changed.wait_timeout(queue, deadline - now).unwrap().0;
+                }
+                None => queue = self.changed.wait(queue).unwrap(),
+            }
+        }
+    }
+}
+
+/// Converts a timeout in milliseconds as taken by the call functions.
+pub(crate) fn timeout_duration(timeout_ms: Option<i32>) -> Option<Duration> {
+    timeout_ms.map(|ms| Duration::from_millis(ms.max(0) as u64))
+}
+
+/// Unregisters a call and completes it with the responses received so far.
+fn finish(id: usize) {
+    let Some(pending) = PENDING.lock().unwrap().remove(&id) else {
+        return;
+    };
+    pending.finish();
+}
+
+/// Responses collected for one asynchronous call.
+struct PendingCall {
+    state: Mutex<Progress>,
+    /// Notified on every response and on completion, for [`ResponseIter`].
+    changed: Condvar,
+}
+
+impl PendingCall {
+    fn finish(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.finished = true;
+        self.notify(&mut state);
+    }
+
+    fn notify(&self, state: &mut Progress) {
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        self.changed.notify_all();
+    }
+}
+
+struct Progress {
+    /// Responses not yet taken by the future or iterator.
+    responses: Vec<ServiceResponse>,
+    received: usize,
+    /// Number of responses after which the call is complete.
+    expected: usize,
+    /// Set when the call was cancelled or timed out.
+    finished: bool,
+    waker: Option<Waker>,
+}
+
+impl Progress {
+    fn is_done(&self) -> bool {
+        self.finished || self.received >= self.expected
+    }
+}
+
+/// Registration of an asynchronous call, shared by both future types.
+struct Call {
+    id: usize,
+    pending: Arc<PendingCall>,
+}
+
+impl Call {
+    /// Registers a call completing after `expected` responses, or with the
+    /// responses received so far once `timeout` elapsed, and starts it.
+    ///
+    /// `start` receives the response callback and its user data, and returns
+    /// whether eCAL accepted the call. A call that is not accepted, or that
+    /// expects no response, completes immediately without responses.
+    fn start(
+        expected: usize,
+        timeout: Option<Duration>,
+        start: impl FnOnce(eCAL_ResponseCallbackT, *mut c_void) -> bool,
+    ) -> Self {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let pending = Arc::new(PendingCall {
+            state: Mutex::new(Progress {
+                responses: Vec::new(),
+                received: 0,
+                expected,
+                finished: false,
+                waker: None,
+            }),
+            changed: Condvar::new(),
+        });
+        let call = Self { id, pending };
+        if expected == 0 {
+            return call;
+        }
+
+        PENDING.lock().unwrap().insert(id, call.pending.clone());
+        if !start(Some(on_response), id as *mut c_void) {
+            call.cancel_handle().cancel();
+        } else if let Some(timeout) = timeout {
+            DEADLINES.schedule(id, Instant::now() + timeout);
+        }
+        call
+    }
+
+    fn cancel_handle(&self) -> CancelHandle {
+        CancelHandle {
+            id: self.id,
+            pending: self.pending.clone(),
+        }
+    }
+
+    /// Returns the state once the call is complete.
+    fn poll_done(&self, cx: &mut Context<'_>) -> Poll<std::sync::MutexGuard<'_, Progress>> {
+        let mut state = self.pending.state.lock().unwrap();
+        if state.is_done() {
+            Poll::Ready(state)
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for Call {
+    fn drop(&mut self) {
+        PENDING.lock().unwrap().remove(&self.id);
+    }
+}
+
+unsafe extern "C" fn on_response(response: *const eCAL_SServiceResponse, user_data: *mut c_void) {
+    if response.is_null() {
+        return;
+    }
+    let id = user_data as usize;
+    // look up without holding the registry lock while handling the response
+    let Some(pending) = PENDING.lock().unwrap().get(&id).cloned() else {
+        return;
+    };
+
+    let response = ServiceResponse::from_struct(unsafe { &*response });
+    let mut state = pending.state.lock().unwrap();
+    if state.is_done() {
+        return;
+    }
+    state.responses.push(response);
+    state.received += 1;
+    if state.is_done() {
+        PENDING.lock().unwrap().remove(&id);
+    }
+    pending.notify(&mut state);
+}
+
+/// Cancels an asynchronous call from any thread.
+///
+/// The future completes immediately with the responses received so far;
+/// later responses are discarded. The request itself cannot be withdrawn, so
+/// servers may still execute it.
+#[derive(Clone)]
+pub struct CancelHandle {
+    id: usize,
+    pending: Arc<PendingCall>,
+}
+
+impl CancelHandle {
+    /// Cancels the call; does nothing if it is already complete.
+    pub fn cancel(&self) {
+        PENDING.lock().unwrap().remove(&self.id);
+        self.pending.finish();
+    }
+}
+
+/// Future of a call expecting a single response, see
+/// [`ServiceClient::call_async`](crate::ServiceClient::call_async).
+///
+/// Resolves to `None` if no server answered, e.g. because none was connected
+/// or the call timed out or was cancelled before. Dropping the future cancels
+/// the call.
+pub struct CallFuture {
+    call: Call,
+}
+
+impl CallFuture {
+    pub(crate) fn start(
+        expected: usize,
+        timeout: Option<Duration>,
+        start: impl FnOnce(eCAL_ResponseCallbackT, *mut c_void) -> bool,
+    ) -> Self {
+        Self {
+            call: Call::start(expected.min(1), timeout, start),
+        }
+    }
+
+    /// Returns a handle to cancel this call from elsewhere.
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.call.cancel_handle()
+    }
+}
+
+impl Future for CallFuture {
+    type Output = Option<ServiceResponse>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.call
+            .poll_done(cx)
+            .map(|mut state| state.responses.pop())
+    }
+}
+
+/// Future of a call on all connected servers, see
+/// [`ServiceClient::call_all_async`](crate::ServiceClient::call_all_async).
+///
+/// Resolves to the responses of all servers connected at call time, in the
+/// order they arrived, or to the responses received until the timeout. Dropping
+/// the future cancels the call.
+pub struct CallAllFuture {
+    call: Call,
+}
+
+impl CallAllFuture {
+    pub(crate) fn start(
+        expected: usize,
+        timeout: Option<Duration>,
+        start: impl FnOnce(eCAL_ResponseCallbackT, *mut c_void) -> bool,
+    ) -> Self {
+        Self {
+            call: Call::start(expected, timeout, start),
+        }
+    }
+
+    /// Returns a handle to cancel this call from elsewhere.
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.call.cancel_handle()
+    }
+}
+
+impl Future for CallAllFuture {
+    type Output = Vec<ServiceResponse>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.call
+            .poll_done(cx)
+            .map(|mut state| std::mem::take(&mut state.responses))
+    }
+}
+
+/// Iterator over the responses of a call on all connected servers, see
+/// [`ServiceClient::call_iter`](crate::ServiceClient::call_iter).
+///
+/// Yields each response as soon as its server answered and ends once every
+/// server connected at call time answered or the timeout elapsed. Dropping
+/// the iterator cancels the call.
+pub struct ResponseIter {
+    call: Call,
+}
+
+impl ResponseIter {
+    pub(crate) fn start(
+        expected: usize,
+        timeout: Option<Duration>,
+        start: impl FnOnce(eCAL_ResponseCallbackT, *mut c_void) -> bool,
+    ) -> Self {
+        Self {
+            call: Call::start(expected, timeout, start),
+        }
+    }
+
+    /// Returns a handle to cancel this call from elsewhere.
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.call.cancel_handle()
+    }
+}
+
+impl Iterator for ResponseIter {
+    type Item = ServiceResponse;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pending = &self.call.pending;
+        let mut state = pending.state.lock().unwrap();
+        loop {
+            if !state.responses.is_empty() {
+                return Some(state.responses.remove(0));
+            }
+            if state.is_done() {
+                return None;
+            }
+            state = pending.changed.wait(state).unwrap();
+        }
+    }
+}
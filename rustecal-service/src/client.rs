@@ -1,34 +1,81 @@
+use crate::async_call::{CallAllFuture, CallFuture, ResponseIter, timeout_duration};
 use crate::client_instance::ClientInstance;
+use crate::events::{ClientEvent, ClientEventSlot};
 use crate::response::ServiceResponse;
 use crate::types::ServiceRequest;
+use rustecal_core::types::EntityId;
 use rustecal_sys::*;
 use std::ffi::CString;
 use std::os::raw::c_void;
 use std::ptr;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often [`ServiceClient::wait_for_server`] checks for a connected server.
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(10);
 
 pub struct ServiceClient {
     pub(crate) handle: *mut eCAL_ServiceClient,
+    /// Released after the client was deleted, so no event arrives afterwards.
+    _events: Option<ClientEventSlot>,
 }
 
 impl ServiceClient {
     pub fn new(service_name: &str) -> Result<Self, String> {
+        Self::create(service_name, None)
+    }
+
+    /// Creates a client reporting connected and disconnected servers to `callback`.
+    ///
+    /// The callback runs on an eCAL thread. At most
+    /// [`MAX_EVENT_CALLBACKS`](crate::events::MAX_EVENT_CALLBACKS) clients
+    /// with event callback can exist at the same time.
+    ///
+    /// ```ignore
+    /// let client = ServiceClient::with_event_callback("mirror", |event| {
+    ///     println!("{:?} server {:?}", event.kind, event.server_id);
+    /// })?;
+    /// ```
+    pub fn with_event_callback<F>(service_name: &str, callback: F) -> Result<Self, String>
+    where
+        F: Fn(ClientEvent) + Send + Sync + 'static,
+    {
+        Self::create(
+            service_name,
+            Some(ClientEventSlot::acquire(Arc::new(callback))?),
+        )
+    }
+
+    fn create(service_name: &str, events: Option<ClientEventSlot>) -> Result<Self, String> {
         let c_service = CString::new(service_name).map_err(|_| "Invalid service name")?;
-        let handle = unsafe { eCAL_ServiceClient_New(c_service.as_ptr(), ptr::null(), 0, None) };
+        let event_callback = events.as_ref().and_then(ClientEventSlot::trampoline);
+        let handle =
+            unsafe { eCAL_ServiceClient_New(c_service.as_ptr(), ptr::null(), 0, event_callback) };
 
         if handle.is_null() {
             Err("Failed to create eCAL_ServiceClient".into())
         } else {
-            Ok(Self { handle })
+            Ok(Self {
+                handle,
+                _events: events,
+            })
         }
     }
 
+    /// Calls a method and returns one response, preferring a successful one.
     pub fn call(
         &self,
         method: &str,
         request: ServiceRequest,
         timeout_ms: Option<i32>,
     ) -> Option<ServiceResponse> {
-        self.call_all(method, request, timeout_ms)?.pop()
+        let mut responses = self.call_all(method, request, timeout_ms)?;
+        let index = responses
+            .iter()
+            .position(|response| response.success)
+            .unwrap_or(responses.len() - 1);
+        Some(responses.swap_remove(index))
     }
 
     pub fn call_all(
@@ -47,7 +94,9 @@ impl ServiceClient {
             .map(|t| t as *const i32)
             .unwrap_or(ptr::null());
 
-        let result = unsafe {
+        // a non-zero result only means that not every instance succeeded;
+        // the responses of all instances, including failed ones, are kept
+        unsafe {
             eCAL_ServiceClient_CallWithResponse(
                 self.handle,
                 c_method.as_ptr(),
@@ -59,7 +108,11 @@ impl ServiceClient {
             )
         };
 
-        if result != 0 || response_ptr.is_null() || response_len == 0 {
+        if response_ptr.is_null() {
+            return None;
+        }
+        if response_len == 0 {
+            unsafe { eCAL_Free(response_ptr as *mut c_void) };
             return None;
         }
 
@@ -77,37 +130,243 @@ impl ServiceClient {
         Some(responses)
     }
 
-    pub fn get_client_instances(&self) -> Vec<ClientInstance> {
-        let mut result = Vec::new();
+    /// Calls a method on all connected servers, handing each response to
+    /// `callback` as soon as its server instance answered.
+    ///
+    /// Blocks until every instance answered or the timeout elapsed. Instances
+    /// that did not answer in time or failed are reported with their call
+    /// state, so a slow instance does not hide the answers of fast ones.
+    /// Returns `true` if the call was executed by all instances.
+    pub fn call_with_callback<F>(
+        &self,
+        method: &str,
+        request: ServiceRequest,
+        timeout_ms: Option<i32>,
+        mut callback: F,
+    ) -> bool
+    where
+        F: FnMut(ServiceResponse),
+    {
+        let Ok(c_method) = CString::new(method) else {
+            return false;
+        };
+        let timeout_ptr = timeout_ms
+            .as_ref()
+            .map(|t| t as *const i32)
+            .unwrap_or(ptr::null());
 
-        unsafe {
-            let list_ptr = eCAL_ServiceClient_GetClientInstances(self.handle);
-            if list_ptr.is_null() {
-                return result;
+        // SAFETY: eCAL invokes the callback only until the blocking call returns,
+        // so `callback` outlives all invocations
+        let (response_callback, user_data) = response_callback(&mut callback);
+        let result = unsafe {
+            eCAL_ServiceClient_CallWithCallback(
+                self.handle,
+                c_method.as_ptr(),
+                request.payload.as_ptr() as *const c_void,
+                request.payload.len(),
+                response_callback,
+                user_data,
+                timeout_ptr,
+            )
+        };
+        result == 0
+    }
+
+    /// Calls a method on all connected servers and yields each response as
+    /// soon as its server instance answered.
+    ///
+    /// Does not block; iterating waits for the next response. The iteration
+    /// ends once every server connected at call time answered or the timeout
+    /// elapsed; servers that did not answer in time are not yielded. The
+    /// iterator does not borrow the client, and dropping it cancels the call.
+    ///
+    /// ```ignore
+    /// for response in client.call_iter("echo", request, Some(500)) {
+    ///     println!("{}: {:?}", response.server_id, response.call_state);
+    /// }
+    /// ```
+    pub fn call_iter(
+        &self,
+        method: &str,
+        request: ServiceRequest,
+        timeout_ms: Option<i32>,
+    ) -> ResponseIter {
+        let expected = self.get_client_instances().len();
+        ResponseIter::start(
+            expected,
+            timeout_duration(timeout_ms),
+            |callback, user_data| self.start_async(method, &request, callback, user_data),
+        )
+    }
+
+    /// Calls a method asynchronously and resolves to the first response.
+    ///
+    /// Does not block the calling thread. The returned future does not borrow
+    /// the client and can be awaited on any executor. With a timeout, it
+    /// resolves to `None` if no server answered in time; dropping the future
+    /// cancels the call. Use [`CallFuture::cancel_handle`] to cancel it from
+    /// elsewhere.
+    pub fn call_async(
+        &self,
+        method: &str,
+        request: ServiceRequest,
+        timeout_ms: Option<i32>,
+    ) -> CallFuture {
+        let expected = self.get_client_instances().len();
+        CallFuture::start(
+            expected,
+            timeout_duration(timeout_ms),
+            |callback, user_data| self.start_async(method, &request, callback, user_data),
+        )
+    }
+
+    /// Calls a method asynchronously on all connected servers.
+    ///
+    /// Resolves once every server connected at call time answered, or with
+    /// the responses received so far once the timeout elapsed, e.g. because a
+    /// server disappeared. See [`call_async`](Self::call_async) for cancellation.
+    pub fn call_all_async(
+        &self,
+        method: &str,
+        request: ServiceRequest,
+        timeout_ms: Option<i32>,
+    ) -> CallAllFuture {
+        let expected = self.get_client_instances().len();
+        CallAllFuture::start(
+            expected,
+            timeout_duration(timeout_ms),
+            |callback, user_data| self.start_async(method, &request, callback, user_data),
+        )
+    }
+
+    fn start_async(
+        &self,
+        method: &str,
+        request: &ServiceRequest,
+        callback: eCAL_ResponseCallbackT,
+        user_data: *mut c_void,
+    ) -> bool {
+        let Ok(c_method) = CString::new(method) else {
+            return false;
+        };
+        let result = unsafe {
+            eCAL_ServiceClient_CallWithCallbackAsync(
+                self.handle,
+                c_method.as_ptr(),
+                request.payload.as_ptr() as *const c_void,
+                request.payload.len(),
+                callback,
+                user_data,
+            )
+        };
+        result == 0
+    }
+
+    /// Returns whether at least one server of the service is connected.
+    pub fn is_connected(&self) -> bool {
+        unsafe { eCAL_ServiceClient_IsConnected(self.handle) != 0 }
+    }
+
+    /// Blocks until a server of the service is connected or `timeout` elapsed.
+    ///
+    /// Returns whether a server is connected.
+    pub fn wait_for_server(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if self.is_connected() {
+                return true;
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return false;
             }
+            thread::sleep(WAIT_POLL_INTERVAL.min(deadline - now));
+        }
+    }
 
-            let mut offset = 0;
-            loop {
-                let instance_ptr = *list_ptr.add(offset);
-                if instance_ptr.is_null() {
-                    break;
-                }
+    /// Returns the first connected instance whose server matches `predicate`.
+    ///
+    /// ```ignore
+    /// let instance = client.select_instance(|server| server.host_name == "vehicle-pc");
+    /// ```
+    pub fn select_instance<P>(&self, predicate: P) -> Option<ClientInstance>
+    where
+        P: Fn(&EntityId) -> bool,
+    {
+        self.get_client_instances()
+            .into_iter()
+            .find(|instance| instance.server_id().is_some_and(|id| predicate(&id)))
+    }
+
+    /// Returns an instance whose server runs on this host, preferring one in
+    /// this process.
+    pub fn local_instance(&self) -> Option<ClientInstance> {
+        let host_name = self.local_host_name()?;
+        let process_id = std::process::id() as i32;
 
-                result.push(ClientInstance::from_raw(instance_ptr));
-                offset += 1;
+        let mut same_host = None;
+        for instance in self.get_client_instances() {
+            let Some(server) = instance.server_id() else {
+                continue;
+            };
+            if server.host_name != host_name {
+                continue;
+            }
+            if server.process_id == process_id {
+                return Some(instance);
             }
+            same_host.get_or_insert(instance);
+        }
+        same_host
+    }
+
+    /// Host name of this client, as reported by eCAL.
+    fn local_host_name(&self) -> Option<String> {
+        let id = unsafe { eCAL_ServiceClient_GetServiceId(self.handle) };
+        if id.is_null() {
+            return None;
         }
+        Some(EntityId::from(unsafe { (*id).service_id }).host_name)
+    }
+
+    /// Returns a snapshot of the instances of all connected servers.
+    ///
+    /// The instances share the list eCAL allocated for them, which is
+    /// released once the last of them is dropped.
+    pub fn get_client_instances(&self) -> Vec<ClientInstance> {
+        unsafe { ClientInstance::from_list(eCAL_ServiceClient_GetClientInstances(self.handle)) }
+    }
+}
 
-        result
+/// Returns the response callback and user data forwarding responses to `callback`.
+///
+/// The caller must keep `callback` alive as long as eCAL may invoke the callback.
+pub(crate) fn response_callback<F>(callback: &mut F) -> (eCAL_ResponseCallbackT, *mut c_void)
+where
+    F: FnMut(ServiceResponse),
+{
+    (
+        Some(forward_response::<F>),
+        callback as *mut F as *mut c_void,
+    )
+}
+
+unsafe extern "C" fn forward_response<F>(
+    response: *const eCAL_SServiceResponse,
+    user_data: *mut c_void,
+) where
+    F: FnMut(ServiceResponse),
+{
+    if response.is_null() || user_data.is_null() {
+        return;
     }
+    let callback = unsafe { &mut *(user_data as *mut F) };
+    callback(ServiceResponse::from_struct(unsafe { &*response }));
 }
 
 impl Drop for ServiceClient {
     fn drop(&mut self) {
         unsafe {
-            let client_instances_ = eCAL_ServiceClient_GetClientInstances(self.handle);
-            eCAL_ClientInstances_Delete(client_instances_);
-
             eCAL_ServiceClient_Delete(self.handle);
         }
     }
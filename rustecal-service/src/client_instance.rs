@@ -1,19 +1,90 @@
+use crate::async_call::{CallFuture, timeout_duration};
+use crate::client::response_callback;
 use crate::response::ServiceResponse;
-use crate::types::{ServiceId, ServiceRequest};
+use crate::types::ServiceRequest;
+use rustecal_core::types::EntityId;
 use rustecal_sys::*;
 use std::ffi::CString;
 use std::os::raw::c_void;
+use std::rc::Rc;
 
 #[derive(Debug)]
 pub struct ClientInstance {
     pub(crate) instance: *mut eCAL_ClientInstance,
+    /// The list this instance was taken from, freed with its last instance.
+    _list: Option<Rc<InstanceList>>,
 }
 
 impl ClientInstance {
+    /// Wraps an instance whose memory is owned by the caller.
     pub fn from_raw(raw: *mut eCAL_ClientInstance) -> Self {
-        Self { instance: raw }
+        Self {
+            instance: raw,
+            _list: None,
+        }
+    }
+
+    /// Takes the instances of a list returned by `eCAL_ServiceClient_GetClientInstances`.
+    ///
+    /// # Safety
+    /// `list` must be null or a null-terminated instance list not owned elsewhere.
+    pub(crate) unsafe fn from_list(list: *mut *mut eCAL_ClientInstance) -> Vec<Self> {
+        if list.is_null() {
+            return Vec::new();
+        }
+        let owner = Rc::new(InstanceList(list));
+
+        let mut instances = Vec::new();
+        let mut offset = 0;
+        loop {
+            let instance = unsafe { *list.add(offset) };
+            if instance.is_null() {
+                break;
+            }
+            instances.push(Self {
+                instance,
+                _list: Some(owner.clone()),
+            });
+            offset += 1;
+        }
+        instances
+    }
+
+    /// Returns whether the server of this instance is connected.
+    pub fn is_connected(&self) -> bool {
+        unsafe { eCAL_ClientInstance_IsConnected(self.instance) != 0 }
     }
 
+    /// Returns the identity of the server this instance calls.
+    pub fn server_id(&self) -> Option<EntityId> {
+        let id = unsafe { eCAL_ClientInstance_GetClientID(self.instance) };
+        if id.is_null() {
+            None
+        } else {
+            Some(EntityId::from(unsafe { *id }))
+        }
+    }
+
+    /// Host name of the server.
+    pub fn host_name(&self) -> Option<String> {
+        self.server_id().map(|id| id.host_name)
+    }
+
+    /// Process id of the server.
+    pub fn process_id(&self) -> Option<i32> {
+        self.server_id().map(|id| id.process_id)
+    }
+
+    /// Entity id of the server.
+    pub fn entity_id(&self) -> Option<u64> {
+        self.server_id().map(|id| id.entity_id)
+    }
+
+    /// Calls a method on this instance.
+    ///
+    /// Failed and timed out calls are returned with the call state and error
+    /// message reported by eCAL. Returns `None` if eCAL produced no response at
+    /// all, e.g. because the method name is invalid or the server is gone.
     pub fn call(
         &self,
         method: &str,
@@ -26,32 +97,60 @@ impl ClientInstance {
             .map(|t| t as *const i32)
             .unwrap_or(std::ptr::null());
 
-        let response_ptr = unsafe {
-            eCAL_ClientInstance_CallWithResponse(
+        // eCAL hands every outcome, including timeouts and failures, to the
+        // callback, while the plain response call only reports success
+        let mut response = None;
+        let mut store = |received: ServiceResponse| response = Some(received);
+
+        // SAFETY: eCAL invokes the callback only until the blocking call returns
+        let (callback, user_data) = response_callback(&mut store);
+        unsafe {
+            eCAL_ClientInstance_CallWithCallback(
                 self.instance,
                 c_method.as_ptr(),
                 request.payload.as_ptr() as *const c_void,
                 request.payload.len(),
+                callback,
+                user_data,
                 timeout_ptr,
             )
         };
+        response
+    }
 
-        if response_ptr.is_null() {
-            return Some(ServiceResponse {
-                success: false,
-                server_id: ServiceId {
-                    service_id: unsafe { std::mem::zeroed() },
-                },
-                error_msg: Some("call failed".into()),
-                payload: vec![],
-            });
-        }
+    /// Calls a method on this instance asynchronously, see
+    /// [`ServiceClient::call_async`](crate::ServiceClient::call_async).
+    pub fn call_async(
+        &self,
+        method: &str,
+        request: ServiceRequest,
+        timeout_ms: Option<i32>,
+    ) -> CallFuture {
+        CallFuture::start(1, timeout_duration(timeout_ms), |callback, user_data| {
+            let Ok(c_method) = CString::new(method) else {
+                return false;
+            };
+            let result = unsafe {
+                eCAL_ClientInstance_CallWithCallbackAsync(
+                    self.instance,
+                    c_method.as_ptr(),
+                    request.payload.as_ptr() as *const c_void,
+                    request.payload.len(),
+                    callback,
+                    user_data,
+                )
+            };
+            result == 0
+        })
+    }
+}
 
-        unsafe {
-            let response = &*response_ptr;
-            let result = ServiceResponse::from_struct(response);
-            eCAL_Free(response_ptr as *mut c_void);
-            Some(result)
-        }
+/// An instance list allocated by eCAL, deleted when dropped.
+#[derive(Debug)]
+struct InstanceList(*mut *mut eCAL_ClientInstance);
+
+impl Drop for InstanceList {
+    fn drop(&mut self) {
+        unsafe { eCAL_ClientInstances_Delete(self.0) };
     }
 }
@@ -0,0 +1,78 @@
+//! Error types of the service API.
+
+use rustecal_pubsub::error::SerializationError;
+use thiserror::Error;
+
+/// Errors returned by [`TypedServiceClient`](crate::TypedServiceClient) calls.
+#[derive(Debug, Error)]
+pub enum CallError {
+    /// No server answered within the timeout.
+    #[error("no response from service")]
+    NoResponse,
+
+    /// The server reported a failure, e.g. because it could not decode the request.
+    #[error("service call failed: {0}")]
+    Failed(String),
+
+    /// The request could not be serialized; nothing was sent.
+    #[error(transparent)]
+    Encode(#[from] SerializationError),
+
+    /// The response could not be deserialized into the expected type.
+    #[error("failed to decode response")]
+    Decode,
+}
+
+/// Prefix of response payloads carrying a [`MethodError`] message.
+///
+/// eCAL has no error message field for method responses, and a non-zero
+/// return state alone may be an application status of a valid response, so
+/// errors are marked explicitly. This is a convention of this crate only;
+/// see [`MethodError`].
+pub(crate) const METHOD_ERROR_MARKER: &[u8] = b"\0rustecal:method-error\0";
+
+/// Error returned by a fallible method handler.
+///
+/// The server answers with the marked message as payload and `ret_state` as
+/// return state of the method. Clients of this crate see such a response with
+/// `CallState::Failed`, the message in `error_msg`, an empty payload and the
+/// return state in `ret_state`.
+///
+/// On the wire the payload is the marker `\0rustecal:method-error\0`
+/// followed by the UTF-8 message. eCAL itself has no notion of method errors,
+/// so clients written against the C, C++, C# or Python API receive an
+/// ordinary response with that raw payload and the non-zero return state.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{message} (state {ret_state})")]
+pub struct MethodError {
+    /// Non-zero return state of the method; `0` is sent as `1`.
+    pub ret_state: i32,
+    pub message: String,
+}
+
+impl MethodError {
+    /// Creates an error with return state `1`.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self::with_state(1, message)
+    }
+
+    /// Creates an error with the given non-zero return state.
+    pub fn with_state(ret_state: i32, message: impl Into<String>) -> Self {
+        Self {
+            ret_state,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<String> for MethodError {
+    fn from(message: String) -> Self {
+        Self::new(message)
+    }
+}
+
+impl From<&str> for MethodError {
+    fn from(message: &str) -> Self {
+        Self::new(message)
+    }
+}
@@ -0,0 +1,189 @@
+//! Connection events of service clients and servers.
+//!
+//! eCAL's event callbacks carry no user data, so each client or server with
+//! an event callback gets one of a fixed number of slots, each with its own
+//! `extern "C"` trampoline forwarding to the closure stored in the slot.
+
+use crate::types::ServiceId;
+use rustecal_sys::*;
+use std::sync::{Arc, RwLock};
+
+/// Maximum number of clients, and separately of servers, with event callbacks per process.
+pub const MAX_EVENT_CALLBACKS: usize = 32;
+
+/// Kind of a [`ClientEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientEventKind {
+    /// A server of the service was found.
+    Connected,
+    /// A server of the service disappeared.
+    Disconnected,
+    /// A server did not answer in time.
+    Timeout,
+}
+
+/// Event reported to a [`ServiceClient`](crate::ServiceClient) about a server.
+#[derive(Debug, Clone)]
+pub struct ClientEvent {
+    pub kind: ClientEventKind,
+    /// The server the event is about.
+    pub server_id: ServiceId,
+    /// Time of the event in microseconds.
+    pub time: i64,
+}
+
+/// Kind of a [`ServerEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServerEventKind {
+    /// A client of the service was found.
+    Connected,
+    /// A client of the service disappeared.
+    Disconnected,
+}
+
+/// Event reported to a [`ServiceServer`](crate::ServiceServer) about a client.
+#[derive(Debug, Clone)]
+pub struct ServerEvent {
+    pub kind: ServerEventKind,
+    /// The client the event is about.
+    pub client_id: ServiceId,
+    /// Time of the event in microseconds.
+    pub time: i64,
+}
+
+pub(crate) type ClientEventCallback = Arc<dyn Fn(ClientEvent) + Send + Sync + 'static>;
+pub(crate) type ServerEventCallback = Arc<dyn Fn(ServerEvent) + Send + Sync + 'static>;
+
+static CLIENT_SLOTS: [RwLock<Option<ClientEventCallback>>; MAX_EVENT_CALLBACKS] =
+    [const { RwLock::new(None) }; MAX_EVENT_CALLBACKS];
+static SERVER_SLOTS: [RwLock<Option<ServerEventCallback>>; MAX_EVENT_CALLBACKS] =
+    [const { RwLock::new(None) }; MAX_EVENT_CALLBACKS];
+
+/// Stores `callback` in a free slot and returns the slot index.
+fn acquire<T>(slots: &[RwLock<Option<T>>], callback: T) -> Result<usize, String> {
+    for (index, slot) in slots.iter().enumerate() {
+        let mut slot = slot.write().unwrap();
+        if slot.is_none() {
+            *slot = Some(callback);
+            return Ok(index);
+        }
+    }
+    Err(format!(
+        "No free event callback slot, at most {MAX_EVENT_CALLBACKS} are supported"
+    ))
+}
+
+/// A client event callback registered in a slot, released on drop.
+pub(crate) struct ClientEventSlot(usize);
+
+impl ClientEventSlot {
+    pub(crate) fn acquire(callback: ClientEventCallback) -> Result<Self, String> {
+        acquire(&CLIENT_SLOTS, callback).map(Self)
+    }
+
+    /// The trampoline to pass to `eCAL_ServiceClient_New`.
+    pub(crate) fn trampoline(&self) -> eCAL_ClientEventCallbackT {
+        CLIENT_TRAMPOLINES[self.0]
+    }
+}
+
+impl Drop for ClientEventSlot {
+    fn drop(&mut self) {
+        *CLIENT_SLOTS[self.0].write().unwrap() = None;
+    }
+}
+
+/// A server event callback registered in a slot, released on drop.
+pub(crate) struct ServerEventSlot(usize);
+
+impl ServerEventSlot {
+    pub(crate) fn acquire(callback: ServerEventCallback) -> Result<Self, String> {
+        acquire(&SERVER_SLOTS, callback).map(Self)
+    }
+
+    /// The trampoline to pass to `eCAL_ServiceServer_New`.
+    pub(crate) fn trampoline(&self) -> eCAL_ServerEventCallbackT {
+        SERVER_TRAMPOLINES[self.0]
+    }
+}
+
+impl Drop for ServerEventSlot {
+    fn drop(&mut self) {
+        *SERVER_SLOTS[self.0].write().unwrap() = None;
+    }
+}
+
+unsafe extern "C" fn on_client_event<const SLOT: usize>(
+    service_id: *const eCAL_SServiceId,
+    data: *const eCAL_SClientEventCallbackData,
+) {
+    if service_id.is_null() || data.is_null() {
+        return;
+    }
+    let data = unsafe { &*data };
+    // event constants may be u32 or i32 depending on eCAL version
+    #[allow(clippy::unnecessary_cast)]
+    let kind = match data.type_ as u32 {
+        x if x == eCAL_eClientEvent_eCAL_eClientEvent_connected as u32 => {
+            ClientEventKind::Connected
+        }
+        x if x == eCAL_eClientEvent_eCAL_eClientEvent_disconnected as u32 => {
+            ClientEventKind::Disconnected
+        }
+        x if x == eCAL_eClientEvent_eCAL_eClientEvent_timeout as u32 => ClientEventKind::Timeout,
+        _ => return,
+    };
+
+    // clone the callback out of the slot, so the lock is not held while it runs
+    let Some(callback) = CLIENT_SLOTS[SLOT].read().unwrap().clone() else {
+        return;
+    };
+    callback(ClientEvent {
+        kind,
+        server_id: unsafe { ServiceId::from_ffi(&*service_id) },
+        time: data.time,
+    });
+}
+
+unsafe extern "C" fn on_server_event<const SLOT: usize>(
+    service_id: *const eCAL_SServiceId,
+    data: *const eCAL_SServerEventCallbackData,
+) {
+    if service_id.is_null() || data.is_null() {
+        return;
+    }
+    let data = unsafe { &*data };
+    // event constants may be u32 or i32 depending on eCAL version
+    #[allow(clippy::unnecessary_cast)]
+    let kind = match data.type_ as u32 {
+        x if x == eCAL_eServerEvent_eCAL_eServerEvent_connected as u32 => {
+            ServerEventKind::Connected
+        }
+        x if x == eCAL_eServerEvent_eCAL_eServerEvent_disconnected as u32 => {
+            ServerEventKind::Disconnected
+        }
+        _ => return,
+    };
+
+    let Some(callback) = SERVER_SLOTS[SLOT].read().unwrap().clone() else {
+        return;
+    };
+    callback(ServerEvent {
+        kind,
+        client_id: unsafe { ServiceId::from_ffi(&*service_id) },
+        time: data.time,
+    });
+}
+
+macro_rules! trampolines {
+    ($handler:ident: $($slot:literal)*) => {
+        [$(Some($handler::<$slot>)),*]
+    };
+}
+
+static CLIENT_TRAMPOLINES: [eCAL_ClientEventCallbackT; MAX_EVENT_CALLBACKS] = trampolines!(
+    on_client_event: 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31
+);
+static SERVER_TRAMPOLINES: [eCAL_ServerEventCallbackT; MAX_EVENT_CALLBACKS] = trampolines!(
+    on_server_event: 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31
+);
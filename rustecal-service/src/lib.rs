@@ -5,6 +5,10 @@
 //! ## Functionality
 //! - `ServiceClient`: send requests to one or many services.
 //! - `ServiceServer`: host services, handle requests with callbacks.
+//! - `TypedServiceClient` / `TypedServiceServer`: the same with typed request
+//!   and response messages (protobuf, serde, string, ...).
+//! - `PolicyClient`: calls single instances with retries, round-robin or
+//!   least-latency selection and hedging.
 //!
 //! ## Example
 //! '''rust
@@ -13,15 +17,27 @@
 //! let response = client.call("Hello!".as_bytes(), std::time::Duration::from_millis(500));
 //! '''
 
+pub mod async_call;
 pub mod client;
 pub mod client_instance;
+pub mod error;
+pub mod events;
+pub mod policy;
 pub mod response;
 pub mod server;
+pub mod typed_client;
+pub mod typed_server;
 pub mod types;
 
 // Public API
+pub use async_call::{CallAllFuture, CallFuture, CancelHandle, ResponseIter};
 pub use client::ServiceClient;
 pub use client_instance::ClientInstance;
+pub use error::{CallError, MethodError};
+pub use events::{ClientEvent, ClientEventKind, ServerEvent, ServerEventKind};
+pub use policy::{CallPolicy, PolicyClient, RetryPolicy, Selection};
+pub use response::ServiceResponse;
 pub use server::ServiceServer;
-pub use types::ServiceRequest;
-pub use types::ServiceResponse;
+pub use typed_client::{TypedResponse, TypedServiceClient};
+pub use typed_server::TypedServiceServer;
+pub use types::{ServiceId, ServiceRequest};
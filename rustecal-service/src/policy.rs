@@ -0,0 +1,353 @@
+//! Retry, instance selection and hedging for service calls.
+//!
+//! A [`PolicyClient`] calls single [`ClientInstance`]s of a service according
+//! to a [`CallPolicy`]:
+//!
+//! ```ignore
+//! let client = ServiceClient::new("mirror")?.with_policy(
+//!     CallPolicy::default()
+//!         .with_timeout(Duration::from_millis(200))
+//!         .with_selection(Selection::LeastLatency)
+//!         .with_retry(RetryPolicy::new(3).with_backoff(Duration::from_millis(50), Duration::from_secs(1)))
+//!         .with_hedge_after(Duration::from_millis(50)),
+//! );
+//! let response = client.call("echo", request);
+//! ```
+
+use crate::async_call::CallFuture;
+use crate::client::ServiceClient;
+use crate::client_instance::ClientInstance;
+use crate::response::ServiceResponse;
+use crate::types::ServiceRequest;
+use rustecal_core::types::EntityId;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+
+/// Weight of the newest sample in the smoothed latency of an instance.
+const LATENCY_WEIGHT: f64 = 0.3;
+
+/// How the instance to call is chosen among the connected ones.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Selection {
+    /// The first connected instance.
+    #[default]
+    First,
+    /// Each call goes to the next instance in turn.
+    RoundRobin,
+    /// The instance with the lowest smoothed response time; instances
+    /// without measurements are tried first.
+    LeastLatency,
+}
+
+/// Retries of failed or timed out calls, with exponential backoff.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one.
+    pub max_attempts: u32,
+    /// Delay before the first retry.
+    pub initial_backoff: Duration,
+    /// Upper bound of the delay; it doubles after every retry.
+    pub max_backoff: Duration,
+    /// Also retry calls a server answered as failed, not only timeouts.
+    pub retry_on_failure: bool,
+}
+
+impl RetryPolicy {
+    /// Creates a policy with `max_attempts` attempts, retrying after 10 ms up to 1 s.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_secs(1),
+            retry_on_failure: true,
+        }
+    }
+
+    /// Sets the delay before the first retry and its upper bound.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// Sets whether calls answered as failed are retried; timeouts always are.
+    pub fn with_retry_on_failure(mut self, retry: bool) -> Self {
+        self.retry_on_failure = retry;
+        self
+    }
+
+    /// Delay before attempt `attempt + 1`.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.min(31));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Settings of a [`PolicyClient`].
+#[derive(Debug, Clone)]
+pub struct CallPolicy {
+    /// Time to wait for a response per attempt.
+    pub timeout: Duration,
+    pub selection: Selection,
+    pub retry: Option<RetryPolicy>,
+    /// Delay after which the request is also sent to a second instance, if
+    /// the first one has not answered yet.
+    pub hedge_after: Option<Duration>,
+}
+
+impl Default for CallPolicy {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(1),
+            selection: Selection::First,
+            retry: None,
+            hedge_after: None,
+        }
+    }
+}
+
+impl CallPolicy {
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_selection(mut self, selection: Selection) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    pub fn with_hedge_after(mut self, delay: Duration) -> Self {
+        self.hedge_after = Some(delay);
+        self
+    }
+}
+
+/// A [`ServiceClient`] calling one instance at a time according to a [`CallPolicy`].
+pub struct PolicyClient {
+    client: ServiceClient,
+    policy: CallPolicy,
+    next: AtomicUsize,
+    /// Smoothed response time per server.
+    latencies: Mutex<HashMap<EntityId, Duration>>,
+}
+
+impl ServiceClient {
+    /// Wraps this client to call instances according to `policy`.
+    pub fn with_policy(self, policy: CallPolicy) -> PolicyClient {
+        PolicyClient::new(self, policy)
+    }
+}
+
+impl PolicyClient {
+    pub fn new(client: ServiceClient, policy: CallPolicy) -> Self {
+        Self {
+            client,
+            policy,
+            next: AtomicUsize::new(0),
+            latencies: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Calls a method according to the policy.
+    ///
+    /// Returns the first successful response. If all attempts failed, returns
+    /// the last failed response, or `None` if no instance answered at all.
+    pub fn call(&self, method: &str, request: ServiceRequest) -> Option<ServiceResponse> {
+        let attempts = self
+            .policy
+            .retry
+            .as_ref()
+            .map_or(1, |retry| retry.max_attempts);
+        let mut last = None;
+
+        for attempt in 0..attempts {
+            match self.attempt(method, &request) {
+                Some(response) if response.success => return Some(response),
+                Some(response) => {
+                    let retry_on_failure = self
+                        .policy
+                        .retry
+                        .as_ref()
+                        .is_some_and(|retry| retry.retry_on_failure);
+                    last = Some(response);
+                    if !retry_on_failure {
+                        break;
+                    }
+                }
+                None => {}
+            }
+
+            if let Some(retry) = &self.policy.retry {
+                if attempt + 1 < attempts {
+                    thread::sleep(retry.backoff(attempt));
+                }
+            }
+        }
+        last
+    }
+
+    /// Returns the policy.
+    pub fn policy(&self) -> &CallPolicy {
+        &self.policy
+    }
+
+    /// Returns the smoothed response time of a server, if it was called before.
+    pub fn latency(&self, server: &EntityId) -> Option<Duration> {
+        self.latencies.lock().unwrap().get(server).copied()
+    }
+
+    /// Returns the underlying client.
+    pub fn client(&self) -> &ServiceClient {
+        &self.client
+    }
+
+    /// Calls one instance, hedged to a second one if configured.
+    ///
+    /// Returns a successful response if any, else a failed one, or `None` on timeout.
+    fn attempt(&self, method: &str, request: &ServiceRequest) -> Option<ServiceResponse> {
+        let mut candidates = self.candidates().into_iter();
+        let (primary, primary_id) = candidates.next()?;
+
+        let start = Instant::now();
+        let deadline = start + self.policy.timeout;
+        let mut hedge = self
+            .policy
+            .hedge_after
+            .map(|delay| start + delay)
+            .filter(|at| *at < deadline)
+            .and_then(|at| candidates.next().map(|candidate| (at, candidate)));
+
+        let mut pending = vec![Pending {
+            server: primary_id,
+            started: start,
+            call: primary.call_async(method, request.clone(), None),
+        }];
+        let mut failed = None;
+
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            let mut index = 0;
+            while index < pending.len() {
+                let Poll::Ready(response) = Pin::new(&mut pending[index].call).poll(&mut cx) else {
+                    index += 1;
+                    continue;
+                };
+                let done = pending.swap_remove(index);
+                self.record(done.server.as_ref(), done.started.elapsed());
+                match response {
+                    Some(response) if response.success => return Some(response),
+                    Some(response) => failed = Some(response),
+                    None => {}
+                }
+            }
+
+            let now = Instant::now();
+            if let Some((at, (instance, server))) = hedge.take() {
+                if now >= at {
+                    pending.push(Pending {
+                        server,
+                        started: now,
+                        call: instance.call_async(method, request.clone(), None),
+                    });
+                    continue;
+                }
+                hedge = Some((at, (instance, server)));
+            }
+
+            // nothing left to wait for
+            if pending.is_empty() && hedge.is_none() {
+                return failed;
+            }
+            if now >= deadline {
+                for timed_out in &pending {
+                    self.record(timed_out.server.as_ref(), self.policy.timeout);
+                }
+                return failed;
+            }
+
+            let wake_at = hedge
+                .as_ref()
+                .map_or(deadline, |(at, _)| (*at).min(deadline));
+            thread::park_timeout(wake_at - now);
+        }
+    }
+
+    /// Connected instances in the order they should be tried.
+    fn candidates(&self) -> Vec<(ClientInstance, Option<EntityId>)> {
+        let mut candidates: Vec<_> = self
+            .client
+            .get_client_instances()
+            .into_iter()
+            .map(|instance| {
+                let server = instance.server_id();
+                (instance, server)
+            })
+            .collect();
+        if candidates.is_empty() {
+            return candidates;
+        }
+
+        match self.policy.selection {
+            Selection::First => {}
+            Selection::RoundRobin => {
+                let next = self.next.fetch_add(1, Ordering::Relaxed);
+                let len = candidates.len();
+                candidates.rotate_left(next % len);
+            }
+            Selection::LeastLatency => {
+                let latencies = self.latencies.lock().unwrap();
+                candidates.sort_by_key(|(_, server)| {
+                    server
+                        .as_ref()
+                        .and_then(|server| latencies.get(server).copied())
+                        .unwrap_or_default()
+                });
+            }
+        }
+        candidates
+    }
+
+    fn record(&self, server: Option<&EntityId>, sample: Duration) {
+        let Some(server) = server else {
+            return;
+        };
+        let mut latencies = self.latencies.lock().unwrap();
+        latencies
+            .entry(server.clone())
+            .and_modify(|latency| {
+                *latency = latency.mul_f64(1.0 - LATENCY_WEIGHT) + sample.mul_f64(LATENCY_WEIGHT)
+            })
+            .or_insert(sample);
+    }
+}
+
+/// A call in flight to one instance.
+struct Pending {
+    server: Option<EntityId>,
+    started: Instant,
+    call: CallFuture,
+}
+
+/// Wakes the thread waiting in [`PolicyClient::attempt`].
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
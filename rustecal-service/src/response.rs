@@ -1,4 +1,5 @@
-use crate::types::{CallState, ServiceId};
+use crate::error::METHOD_ERROR_MARKER;
+use crate::types::{CallState, ServiceId, cstr_to_string};
 use rustecal_sys::*;
 use std::ffi::CStr;
 
@@ -7,7 +8,13 @@ use std::ffi::CStr;
 #[derive(Debug, Clone)]
 pub struct ServiceResponse {
     pub success: bool,
+    /// Call state of the answering instance, e.g. `Timeout` if it did not answer in time.
+    pub call_state: CallState,
     pub server_id: ServiceId,
+    /// Return state of the server's method callback. Servers may use it as
+    /// application status of an executed call; only an error of a fallible
+    /// method of this crate sets `call_state` to `Failed`.
+    pub ret_state: i32,
     pub error_msg: Option<String>,
     pub payload: Vec<u8>,
 }
@@ -15,11 +22,14 @@ pub struct ServiceResponse {
 impl ServiceResponse {
     /// Parses a raw FFI struct into a safe Rust response object.
     pub fn from_struct(response: &eCAL_SServiceResponse) -> Self {
-        let success = CallState::from(response.call_state).is_success();
+        let mut call_state = CallState::from(response.call_state);
 
-        let server_id = unsafe { ServiceId::from_ffi(&response.server_id) };
+        let method_name =
+            unsafe { cstr_to_string(response.service_method_information.method_name) };
+        let server_id =
+            unsafe { ServiceId::from_ffi(&response.server_id) }.with_method(method_name);
 
-        let error_msg = if response.error_msg.is_null() {
+        let mut error_msg = if response.error_msg.is_null() {
             None
         } else {
             Some(unsafe {
@@ -29,7 +39,7 @@ impl ServiceResponse {
             })
         };
 
-        let payload = if response.response.is_null() || response.response_length == 0 {
+        let mut payload = if response.response.is_null() || response.response_length == 0 {
             vec![]
         } else {
             unsafe {
@@ -38,9 +48,21 @@ impl ServiceResponse {
             }
         };
 
+        // a non-zero return state alone may be an application status, only
+        // responses marked by a failing handler of this crate are errors
+        if call_state.is_success() && response.ret_state != 0 {
+            if let Some(message) = payload.strip_prefix(METHOD_ERROR_MARKER) {
+                call_state = CallState::Failed;
+                error_msg = Some(String::from_utf8_lossy(message).into_owned());
+                payload.clear();
+            }
+        }
+
         Self {
-            success,
+            success: call_state.is_success(),
+            call_state,
             server_id,
+            ret_state: response.ret_state,
             error_msg,
             payload,
         }
@@ -1,42 +1,163 @@
-use crate::types::{MethodInfo, ServiceCallback};
+use crate::error::{METHOD_ERROR_MARKER, MethodError};
+use crate::events::{ServerEvent, ServerEventSlot};
+use crate::types::{FallibleServiceCallback, MethodInfo, ServiceCallback};
+use rustecal_core::types::DataTypeInfo;
 use rustecal_sys::*;
 use std::collections::HashMap;
 use std::ffi::{CStr, CString};
 use std::os::raw::{c_int, c_void};
 use std::ptr;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, RwLock};
 
-type SharedCallback = Arc<Mutex<HashMap<String, ServiceCallback>>>;
+/// Handler stored per method; an `Err` is returned to the caller as a failed call.
+pub(crate) type MethodHandler = FallibleServiceCallback;
+
+/// Handlers by method name. The lock is only held to look up or swap a
+/// handler, never while one runs, so methods execute in parallel and
+/// handlers may register or remove methods of their own server.
+type MethodTable = RwLock<HashMap<String, Arc<MethodHandler>>>;
+type SharedCallback = Arc<MethodTable>;
 
 /// Represents a service server that can handle RPC-style requests.
 pub struct ServiceServer {
     handle: *mut eCAL_ServiceServer,
     callbacks: SharedCallback,
+    /// Released after the server was deleted, so no event arrives afterwards.
+    _events: Option<ServerEventSlot>,
 }
 
 impl ServiceServer {
     pub fn new(service_name: &str) -> Result<Self, String> {
+        Self::create(service_name, None)
+    }
+
+    /// Creates a server reporting connected and disconnected clients to `callback`.
+    ///
+    /// The callback runs on an eCAL thread. At most
+    /// [`MAX_EVENT_CALLBACKS`](crate::events::MAX_EVENT_CALLBACKS) servers
+    /// with event callback can exist at the same time.
+    pub fn with_event_callback<F>(service_name: &str, callback: F) -> Result<Self, String>
+    where
+        F: Fn(ServerEvent) + Send + Sync + 'static,
+    {
+        Self::create(
+            service_name,
+            Some(ServerEventSlot::acquire(Arc::new(callback))?),
+        )
+    }
+
+    fn create(service_name: &str, events: Option<ServerEventSlot>) -> Result<Self, String> {
         let c_service_name = CString::new(service_name).map_err(|_| "Invalid service name")?;
 
-        let callbacks: SharedCallback = Arc::new(Mutex::new(HashMap::new()));
-        let handle = unsafe { eCAL_ServiceServer_New(c_service_name.as_ptr(), None) };
+        let callbacks: SharedCallback = Arc::new(RwLock::new(HashMap::new()));
+        let event_callback = events.as_ref().and_then(ServerEventSlot::trampoline);
+        let handle = unsafe { eCAL_ServiceServer_New(c_service_name.as_ptr(), event_callback) };
         if handle.is_null() {
             return Err("Failed to create eCAL_ServiceServer".into());
         }
 
-        Ok(Self { handle, callbacks })
+        Ok(Self {
+            handle,
+            callbacks,
+            _events: events,
+        })
+    }
+
+    pub fn add_method(&self, method: &str, callback: ServiceCallback) -> Result<(), String> {
+        self.add_handler(
+            method,
+            None,
+            Box::new(move |info, request| Ok(callback(info, request))),
+        )
+    }
+
+    /// Registers a method together with its request and response data types.
+    ///
+    /// The types are published with the service, so eCAL Monitor and the
+    /// monitoring snapshot show them and generic clients can discover how to
+    /// call the method. They are also passed to the callback in [`MethodInfo`].
+    pub fn add_method_with_types(
+        &self,
+        method: &str,
+        request_type: DataTypeInfo,
+        response_type: DataTypeInfo,
+        callback: ServiceCallback,
+    ) -> Result<(), String> {
+        self.add_handler(
+            method,
+            Some((request_type, response_type)),
+            Box::new(move |info, request| Ok(callback(info, request))),
+        )
     }
 
-    pub fn add_method(&mut self, method: &str, callback: ServiceCallback) -> Result<(), String> {
+    /// Registers a method whose callback can fail.
+    ///
+    /// An `Err` is answered with the error message as payload and the non-zero
+    /// return state of the [`MethodError`]; clients of this crate see it as
+    /// failed call with the message in `ServiceResponse::error_msg`.
+    ///
+    /// The message is prefixed with the marker `\0rustecal:method-error\0`,
+    /// a convention only this crate understands. Clients using another eCAL
+    /// API receive a regular response with the marked payload, so methods
+    /// called from other languages should rather report errors in their
+    /// response type.
+    ///
+    /// ```ignore
+    /// server.add_fallible_method("divide", Box::new(|_info, request| {
+    ///     let (a, b) = parse(request)?;
+    ///     if b == 0 {
+    ///         return Err(MethodError::with_state(2, "division by zero"));
+    ///     }
+    ///     Ok((a / b).to_le_bytes().to_vec())
+    /// }))?;
+    /// ```
+    pub fn add_fallible_method(
+        &self,
+        method: &str,
+        callback: FallibleServiceCallback,
+    ) -> Result<(), String> {
+        self.add_handler(method, None, callback)
+    }
+
+    /// Registers a fallible method together with its request and response data types.
+    pub fn add_fallible_method_with_types(
+        &self,
+        method: &str,
+        request_type: DataTypeInfo,
+        response_type: DataTypeInfo,
+        callback: FallibleServiceCallback,
+    ) -> Result<(), String> {
+        self.add_handler(method, Some((request_type, response_type)), callback)
+    }
+
+    pub(crate) fn add_handler(
+        &self,
+        method: &str,
+        types: Option<(DataTypeInfo, DataTypeInfo)>,
+        handler: MethodHandler,
+    ) -> Result<(), String> {
         let c_method = CString::new(method).map_err(|_| "Invalid method name")?;
 
+        // eCAL copies the method information, so it only has to outlive the call below
+        let ffi_types = types
+            .as_ref()
+            .map(|(request, response)| {
+                Ok::<_, String>((FfiDataType::new(request)?, FfiDataType::new(response)?))
+            })
+            .transpose()?;
+
         let mut method_info: eCAL_SServiceMethodInformation = unsafe { std::mem::zeroed() };
         method_info.method_name = c_method.as_ptr();
+        if let Some((request, response)) = &ffi_types {
+            method_info.request_type = request.as_ffi();
+            method_info.response_type = response.as_ffi();
+        }
 
+        // replaces the handler of an already registered method
         self.callbacks
-            .lock()
+            .write()
             .unwrap()
-            .insert(method.to_string(), callback);
+            .insert(method.to_string(), Arc::new(handler));
 
         let result = unsafe {
             eCAL_ServiceServer_SetMethodCallback(
@@ -54,6 +175,24 @@ impl ServiceServer {
         }
     }
 
+    /// Removes a method; calls already running finish with the removed handler.
+    ///
+    /// Returns `false` if the method was not registered.
+    pub fn remove_method(&self, method: &str) -> bool {
+        let Ok(c_method) = CString::new(method) else {
+            return false;
+        };
+        unsafe { eCAL_ServiceServer_RemoveMethodCallback(self.handle, c_method.as_ptr()) };
+        self.callbacks.write().unwrap().remove(method).is_some()
+    }
+
+    /// Returns the names of all registered methods.
+    pub fn methods(&self) -> Vec<String> {
+        let mut methods: Vec<String> = self.callbacks.read().unwrap().keys().cloned().collect();
+        methods.sort();
+        methods
+    }
+
     unsafe extern "C" fn dispatch(
         method_info: *const eCAL_SServiceMethodInformation,
         request_ptr: *const c_void,
@@ -62,10 +201,7 @@ impl ServiceServer {
         response_len: *mut usize,
         user_data: *mut c_void,
     ) -> c_int {
-        let callbacks = {
-            let raw = user_data as *const Mutex<HashMap<String, ServiceCallback>>;
-            unsafe { &*raw }.lock().unwrap()
-        };
+        let callbacks = unsafe { &*(user_data as *const MethodTable) };
 
         let method_name = {
             if method_info.is_null() || unsafe { (*method_info).method_name }.is_null() {
@@ -78,6 +214,7 @@ impl ServiceServer {
                 Err(_) => return 1,
             }
         };
+        let info = unsafe { MethodInfo::from_ffi(&*method_info) };
 
         let request = if request_ptr.is_null() || request_len == 0 {
             &[]
@@ -85,18 +222,19 @@ impl ServiceServer {
             unsafe { std::slice::from_raw_parts(request_ptr as *const u8, request_len) }
         };
 
-        let info = MethodInfo {
-            method_name: method_name.clone(),
-            request_type: None,
-            response_type: None,
-        };
-
-        let cb = match callbacks.get(&method_name) {
-            Some(cb) => cb,
+        // clone the handler out of the table, so the lock is not held while it runs
+        let cb = match callbacks.read().unwrap().get(&method_name) {
+            Some(cb) => cb.clone(),
             None => return 1,
         };
 
-        let response = cb(info, request);
+        let (response, ret_state) = match cb(info, request) {
+            Ok(response) => (response, 0),
+            Err(MethodError { ret_state, message }) => (
+                [METHOD_ERROR_MARKER, message.as_bytes()].concat(),
+                if ret_state == 0 { 1 } else { ret_state },
+            ),
+        };
 
         let buffer = unsafe { eCAL_Malloc(response.len()) };
         if buffer.is_null() {
@@ -109,10 +247,46 @@ impl ServiceServer {
             *response_len = response.len();
         }
 
-        0
+        ret_state
+    }
+}
+
+/// Owned C strings backing an `eCAL_SDataTypeInformation`.
+struct FfiDataType<'a> {
+    encoding: CString,
+    name: CString,
+    descriptor: &'a [u8],
+}
+
+impl<'a> FfiDataType<'a> {
+    fn new(info: &'a DataTypeInfo) -> Result<Self, String> {
+        Ok(Self {
+            encoding: CString::new(info.encoding.as_str())
+                .map_err(|_| "Invalid encoding string")?,
+            name: CString::new(info.type_name.as_str()).map_err(|_| "Invalid type name")?,
+            descriptor: &info.descriptor,
+        })
+    }
+
+    fn as_ffi(&self) -> eCAL_SDataTypeInformation {
+        eCAL_SDataTypeInformation {
+            encoding: self.encoding.as_ptr(),
+            name: self.name.as_ptr(),
+            descriptor: if self.descriptor.is_empty() {
+                ptr::null()
+            } else {
+                self.descriptor.as_ptr() as *const c_void
+            },
+            descriptor_length: self.descriptor.len(),
+        }
     }
 }
 
+// SAFETY: eCAL service servers may be used from any thread, and the method
+// table is synchronized, so handlers can share their own server
+unsafe impl Send for ServiceServer {}
+unsafe impl Sync for ServiceServer {}
+
 impl Drop for ServiceServer {
     fn drop(&mut self) {
         unsafe {
@@ -0,0 +1,177 @@
+use crate::client::ServiceClient;
+use crate::error::CallError;
+use crate::events::ClientEvent;
+use crate::response::ServiceResponse;
+use crate::types::{ServiceId, ServiceRequest};
+use rustecal_pubsub::typed_publisher::PublisherMessage;
+use rustecal_pubsub::typed_subscriber::SubscriberMessage;
+
+/// A decoded response of a typed service call.
+#[derive(Debug, Clone)]
+pub struct TypedResponse<T> {
+    /// The deserialized response message.
+    pub payload: T,
+    /// The server instance that answered.
+    pub server_id: ServiceId,
+    /// Return state of the server's method, e.g. an application status.
+    pub ret_state: i32,
+}
+
+/// A service client calling methods with typed requests and responses.
+///
+/// The counterpart of [`TypedServiceServer`](crate::TypedServiceServer); the
+/// message types are chosen per call, as methods of one service may use
+/// different types.
+pub struct TypedServiceClient {
+    client: ServiceClient,
+}
+
+impl TypedServiceClient {
+    /// Creates a new client for the given service.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err(String)` if the underlying eCAL client could not be created.
+    pub fn new(service_name: &str) -> Result<Self, String> {
+        Ok(Self {
+            client: ServiceClient::new(service_name)?,
+        })
+    }
+
+    /// Creates a new client reporting server connection events, see
+    /// [`ServiceClient::with_event_callback`].
+    pub fn with_event_callback<F>(service_name: &str, callback: F) -> Result<Self, String>
+    where
+        F: Fn(ClientEvent) + Send + Sync + 'static,
+    {
+        Ok(Self {
+            client: ServiceClient::with_event_callback(service_name, callback)?,
+        })
+    }
+
+    /// Calls a method and returns the decoded response of one server.
+    ///
+    /// ```ignore
+    /// let response = client.call::<_, ProtobufMessage<SFloat>>("Add", &request, Some(1000))?;
+    /// println!("result: {}", response.payload.data.out);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CallError`] if the request cannot be encoded, no server
+    /// answers, the server reports a failure, or the response cannot be decoded.
+    pub fn call<Req, Resp>(
+        &self,
+        method: &str,
+        request: &Req,
+        timeout_ms: Option<i32>,
+    ) -> Result<TypedResponse<Resp>, CallError>
+    where
+        Req: PublisherMessage,
+        Resp: for<'a> SubscriberMessage<'a>,
+    {
+        let response = self
+            .client
+            .call(method, encode(request)?, timeout_ms)
+            .ok_or(CallError::NoResponse)?;
+        decode(response)
+    }
+
+    /// Calls a method on all connected servers and returns one result per answering server.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CallError`] if the request cannot be encoded or no server answers.
+    pub fn call_all<Req, Resp>(
+        &self,
+        method: &str,
+        request: &Req,
+        timeout_ms: Option<i32>,
+    ) -> Result<Vec<Result<TypedResponse<Resp>, CallError>>, CallError>
+    where
+        Req: PublisherMessage,
+        Resp: for<'a> SubscriberMessage<'a>,
+    {
+        let responses = self
+            .client
+            .call_all(method, encode(request)?, timeout_ms)
+            .ok_or(CallError::NoResponse)?;
+        Ok(responses.into_iter().map(decode).collect())
+    }
+
+    /// Calls a method asynchronously and resolves to the decoded first response.
+    ///
+    /// The future does not borrow the client; dropping it cancels the call.
+    /// See [`ServiceClient::call_async`].
+    pub fn call_async<Req, Resp>(
+        &self,
+        method: &str,
+        request: &Req,
+        timeout_ms: Option<i32>,
+    ) -> impl Future<Output = Result<TypedResponse<Resp>, CallError>> + use<Req, Resp>
+    where
+        Req: PublisherMessage,
+        Resp: for<'a> SubscriberMessage<'a>,
+    {
+        let call =
+            encode(request).map(|request| self.client.call_async(method, request, timeout_ms));
+        async move { decode(call?.await.ok_or(CallError::NoResponse)?) }
+    }
+
+    /// Calls a method asynchronously on all connected servers, see
+    /// [`ServiceClient::call_all_async`].
+    pub fn call_all_async<Req, Resp>(
+        &self,
+        method: &str,
+        request: &Req,
+        timeout_ms: Option<i32>,
+    ) -> impl Future<Output = Result<Vec<Result<TypedResponse<Resp>, CallError>>, CallError>>
+    + use<Req, Resp>
+    where
+        Req: PublisherMessage,
+        Resp: for<'a> SubscriberMessage<'a>,
+    {
+        let call =
+            encode(request).map(|request| self.client.call_all_async(method, request, timeout_ms));
+        async move {
+            let responses = call?.await;
+            if responses.is_empty() {
+                return Err(CallError::NoResponse);
+            }
+            Ok(responses.into_iter().map(decode).collect())
+        }
+    }
+
+    /// Returns the underlying untyped client, e.g. to call single instances.
+    pub fn client(&self) -> &ServiceClient {
+        &self.client
+    }
+}
+
+fn encode<Req: PublisherMessage>(request: &Req) -> Result<ServiceRequest, CallError> {
+    Ok(ServiceRequest {
+        payload: request.to_bytes()?.to_vec(),
+    })
+}
+
+/// Maps failed calls onto [`CallError::Failed`] and decodes successful ones.
+fn decode<Resp>(response: ServiceResponse) -> Result<TypedResponse<Resp>, CallError>
+where
+    Resp: for<'a> SubscriberMessage<'a>,
+{
+    if !response.success {
+        return Err(CallError::Failed(
+            response
+                .error_msg
+                .unwrap_or_else(|| "call not executed".to_string()),
+        ));
+    }
+
+    let payload =
+        Resp::from_bytes(&response.payload, &Resp::datatype()).ok_or(CallError::Decode)?;
+    Ok(TypedResponse {
+        payload,
+        server_id: response.server_id,
+        ret_state: response.ret_state,
+    })
+}
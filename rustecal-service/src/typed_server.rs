@@ -0,0 +1,104 @@
+use crate::error::MethodError;
+use crate::events::ServerEvent;
+use crate::server::ServiceServer;
+use crate::types::MethodInfo;
+use rustecal_pubsub::typed_publisher::PublisherMessage;
+use rustecal_pubsub::typed_subscriber::SubscriberMessage;
+
+/// A service server whose methods take and return typed messages.
+///
+/// Request and response types implement the same message traits as typed
+/// publishers and subscribers (e.g. `StringMessage`, `ProtobufMessage<T>`,
+/// `JsonMessage<T>`), so decoding and encoding happen automatically. A request
+/// that cannot be decoded, or a response that cannot be encoded, is returned
+/// to the caller as a failed call with an error message.
+pub struct TypedServiceServer {
+    server: ServiceServer,
+}
+
+impl TypedServiceServer {
+    /// Creates a new service server.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err(String)` if the underlying eCAL server could not be created.
+    pub fn new(service_name: &str) -> Result<Self, String> {
+        Ok(Self {
+            server: ServiceServer::new(service_name)?,
+        })
+    }
+
+    /// Creates a new service server reporting client connection events, see
+    /// [`ServiceServer::with_event_callback`].
+    pub fn with_event_callback<F>(service_name: &str, callback: F) -> Result<Self, String>
+    where
+        F: Fn(ServerEvent) + Send + Sync + 'static,
+    {
+        Ok(Self {
+            server: ServiceServer::with_event_callback(service_name, callback)?,
+        })
+    }
+
+    /// Registers a method handling requests of type `Req` with responses of type `Resp`.
+    ///
+    /// The data types of `Req` and `Resp` are published with the method.
+    ///
+    /// ```ignore
+    /// server.add_method("Add", |_info, request: ProtobufMessage<SFloatTuple>| {
+    ///     ProtobufMessage::from(SFloat { out: request.data.inp1 + request.data.inp2 })
+    /// })?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err(String)` if the method could not be registered.
+    pub fn add_method<Req, Resp, F>(&self, method: &str, handler: F) -> Result<(), String>
+    where
+        Req: for<'a> SubscriberMessage<'a>,
+        Resp: PublisherMessage,
+        F: Fn(MethodInfo, Req) -> Resp + Send + Sync + 'static,
+    {
+        self.add_fallible_method(method, move |info, request: Req| Ok(handler(info, request)))
+    }
+
+    /// Registers a method whose handler can fail.
+    ///
+    /// An `Err` is returned to the caller as failed call, see
+    /// [`ServiceServer::add_fallible_method`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err(String)` if the method could not be registered.
+    pub fn add_fallible_method<Req, Resp, F>(&self, method: &str, handler: F) -> Result<(), String>
+    where
+        Req: for<'a> SubscriberMessage<'a>,
+        Resp: PublisherMessage,
+        F: Fn(MethodInfo, Req) -> Result<Resp, MethodError> + Send + Sync + 'static,
+    {
+        let request_type = Req::datatype();
+        self.server.add_handler(
+            method,
+            Some((request_type.clone(), Resp::datatype())),
+            Box::new(move |info, bytes| {
+                let request = Req::from_bytes(bytes, &request_type).ok_or_else(|| {
+                    format!("failed to decode request for method '{}'", info.method_name)
+                })?;
+                let response = handler(info, request)?;
+                response
+                    .to_bytes()
+                    .map(|bytes| bytes.to_vec())
+                    .map_err(|error| MethodError::new(error.to_string()))
+            }),
+        )
+    }
+
+    /// Removes a method, see [`ServiceServer::remove_method`].
+    pub fn remove_method(&self, method: &str) -> bool {
+        self.server.remove_method(method)
+    }
+
+    /// Returns the underlying untyped server, e.g. to register byte-level methods.
+    pub fn server(&mut self) -> &mut ServiceServer {
+        &mut self.server
+    }
+}
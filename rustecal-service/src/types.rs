@@ -1,4 +1,8 @@
+use rustecal_core::types::{DataTypeInfo, EntityId};
 use rustecal_sys::*;
+use std::ffi::CStr;
+use std::fmt;
+use std::os::raw::c_char;
 
 #[derive(Debug, Clone, Copy)]
 pub enum CallState {
@@ -40,44 +44,105 @@ impl From<u32> for CallState {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+/// Identifies a service server or client instance, and for responses the
+/// called method.
+///
+/// Owns its data, so ids can be kept, hashed and sent across threads after
+/// eCAL released the raw structure they were read from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ServiceId {
-    pub service_id: eCAL_SEntityId,
+    pub service_id: EntityId,
+    pub service_name: String,
+    /// Name of the called method; empty where eCAL does not report one, e.g. in connection events.
+    pub method_name: String,
 }
 
 impl ServiceId {
-    /// Constructs a `ServiceId` from its FFI representation.
+    /// Constructs a `ServiceId` from its FFI representation, without method name.
     ///
     /// # Safety
     /// - `raw` must refer to a valid, properly initialized `eCAL_SServiceId`.
-    /// - Any pointers inside `raw` must be non-null and correctly aligned.
+    /// - The string pointers inside `raw` must be null or point to valid NUL-terminated strings.
     /// - The memory behind `raw` must remain valid for the duration of this call.
     pub unsafe fn from_ffi(raw: &eCAL_SServiceId) -> Self {
         Self {
-            service_id: raw.service_id,
+            service_id: EntityId::from(raw.service_id),
+            service_name: unsafe { cstr_to_string(raw.service_name) },
+            method_name: String::new(),
         }
     }
+
+    /// Returns this id with the given method name.
+    pub fn with_method(mut self, method_name: impl Into<String>) -> Self {
+        self.method_name = method_name.into();
+        self
+    }
 }
 
-#[derive(Debug, Clone)]
-pub struct ServiceRequest {
-    pub payload: Vec<u8>,
+impl fmt::Display for ServiceId {
+    /// Formats as `service[.method]@host:process_id/entity_id`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.service_name)?;
+        if !self.method_name.is_empty() {
+            write!(f, ".{}", self.method_name)?;
+        }
+        write!(
+            f,
+            "@{}:{}/{}",
+            self.service_id.host_name, self.service_id.process_id, self.service_id.entity_id
+        )
+    }
 }
 
 #[derive(Debug, Clone)]
-pub struct ServiceResponse {
-    pub success: bool,
-    pub server_id: ServiceId,
-    pub error_msg: Option<String>,
+pub struct ServiceRequest {
     pub payload: Vec<u8>,
 }
 
 /// Metadata passed to method callbacks about the method interface.
+///
+/// The data types are `None` for methods registered without type information.
 #[derive(Debug, Clone)]
 pub struct MethodInfo {
     pub method_name: String,
-    pub request_type: Option<String>,
-    pub response_type: Option<String>,
+    pub request_type: Option<DataTypeInfo>,
+    pub response_type: Option<DataTypeInfo>,
+}
+
+impl MethodInfo {
+    /// Converts the method information eCAL passes to method callbacks.
+    ///
+    /// # Safety
+    /// - `raw.method_name` must be null or point to a valid NUL-terminated string.
+    /// - The data type pointers inside `raw` must be null or valid for their lengths.
+    pub unsafe fn from_ffi(raw: &eCAL_SServiceMethodInformation) -> Self {
+        Self {
+            method_name: unsafe { cstr_to_string(raw.method_name) },
+            request_type: data_type(raw.request_type),
+            response_type: data_type(raw.response_type),
+        }
+    }
+}
+
+/// Copies a C string, mapping null to an empty string.
+///
+/// # Safety
+/// `ptr` must be null or point to a valid NUL-terminated string.
+pub(crate) unsafe fn cstr_to_string(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        String::new()
+    } else {
+        unsafe { CStr::from_ptr(ptr) }
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+/// Returns `None` for the empty data type of untyped methods.
+fn data_type(raw: eCAL_SDataTypeInformation) -> Option<DataTypeInfo> {
+    let info = DataTypeInfo::from(raw);
+    let empty = info.type_name.is_empty() && info.encoding.is_empty() && info.descriptor.is_empty();
+    (!empty).then_some(info)
 }
 
 /// The service callback signature used by ServiceServer.
@@ -86,3 +151,11 @@ pub struct MethodInfo {
 /// - Accepts `MethodInfo` and a reference to request bytes
 /// - Returns response bytes (`Vec<u8>`)
 pub type ServiceCallback = Box<dyn Fn(MethodInfo, &[u8]) -> Vec<u8> + Send + Sync + 'static>;
+
+/// The signature of fallible method handlers, see
+/// [`ServiceServer::add_fallible_method`](crate::ServiceServer::add_fallible_method).
+///
+/// An `Err` is sent back to the caller as failed call with the error message.
+pub type FallibleServiceCallback = Box<
+    dyn Fn(MethodInfo, &[u8]) -> Result<Vec<u8>, crate::error::MethodError> + Send + Sync + 'static,
+>;
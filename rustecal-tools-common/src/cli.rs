@@ -0,0 +1,30 @@
+//! Command line argument parsing.
+
+use std::time::Duration;
+
+/// Parses a duration like `90s` or `5m`.
+///
+/// Accepts the suffixes `ms`, `s`, `m` and `h`; a number without suffix is
+/// taken as seconds.
+pub fn parse_duration(text: &str) -> Result<Duration, String> {
+    let split = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid duration '{text}'"))?;
+    let seconds = |factor: u64| {
+        number
+            .checked_mul(factor)
+            .map(Duration::from_secs)
+            .ok_or_else(|| format!("duration '{text}' is too large"))
+    };
+    match unit {
+        "ms" => Ok(Duration::from_millis(number)),
+        "" | "s" => seconds(1),
+        "m" => seconds(60),
+        "h" => seconds(3600),
+        _ => Err(format!("invalid duration '{text}'")),
+    }
+}
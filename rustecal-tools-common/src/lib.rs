@@ -0,0 +1,10 @@
+//! # rustecal-tools-common
+//!
+//! Pieces shared by `rustecal-record` and `rustecal-play`, so the player does
+//! not depend on the recorder:
+//!
+//! - [`mcap`]: the conventions of eCAL recordings in MCAP files
+//! - [`cli`]: command line argument parsing
+
+pub mod cli;
+pub mod mcap;
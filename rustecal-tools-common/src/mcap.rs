@@ -0,0 +1,6 @@
+//! Conventions of eCAL recordings in MCAP files.
+
+/// Channel metadata key holding the eCAL encoding.
+pub const META_ENCODING: &str = "ecal.encoding";
+/// Channel metadata key holding the eCAL type name.
+pub const META_TYPE_NAME: &str = "ecal.type_name";
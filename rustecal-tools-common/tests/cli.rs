@@ -0,0 +1,19 @@
+use rustecal_tools_common::cli::parse_duration;
+use std::time::Duration;
+
+#[test]
+fn parses_durations() {
+    assert_eq!(parse_duration("250ms"), Ok(Duration::from_millis(250)));
+    assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+    assert_eq!(parse_duration("90s"), Ok(Duration::from_secs(90)));
+    assert_eq!(parse_duration("5m"), Ok(Duration::from_secs(300)));
+    assert_eq!(parse_duration("2h"), Ok(Duration::from_secs(7200)));
+}
+
+#[test]
+fn rejects_invalid_durations() {
+    assert!(parse_duration("").is_err());
+    assert!(parse_duration("5d").is_err());
+    assert!(parse_duration("-5s").is_err());
+    assert!(parse_duration(&format!("{}h", u64::MAX)).is_err());
+}
@@ -0,0 +1,173 @@
+//! # rustecal-types-arrow
+//!
+//! Provides support for Apache Arrow record batches with rustecal.
+//!
+//! Each message is an Arrow IPC stream (schema, one record batch, end-of-stream
+//! marker), so it can be read by any Arrow implementation, e.g.
+//! `pyarrow.ipc.open_stream(payload).read_all()`. The descriptor holds the
+//! schema of the topic as an IPC stream without batches.
+
+use arrow_array::RecordBatch;
+use arrow_ipc::reader::StreamReader;
+use arrow_ipc::writer::StreamWriter;
+use arrow_schema::{ArrowError, SchemaRef};
+use rustecal_core::types::DataTypeInfo;
+use rustecal_pubsub::error::SerializationError;
+use rustecal_pubsub::payload_writer::PayloadWriter;
+use rustecal_pubsub::typed_publisher::PublisherMessage;
+use rustecal_pubsub::typed_subscriber::SubscriberMessage;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// Describes the Arrow schema of a topic.
+///
+/// eCAL announces the data type per topic, so the schema has to be known at
+/// the type level:
+///
+/// ```ignore
+/// struct Imu;
+///
+/// impl ArrowSchema for Imu {
+///     const TYPE_NAME: &'static str = "sensors.Imu";
+///
+///     fn schema() -> SchemaRef {
+///         Arc::new(Schema::new(vec![
+///             Field::new("timestamp", DataType::Int64, false),
+///             Field::new("acc_x", DataType::Float32, false),
+///         ]))
+///     }
+/// }
+/// ```
+pub trait ArrowSchema {
+    /// Type name announced to eCAL.
+    const TYPE_NAME: &'static str;
+
+    /// Schema of all record batches sent on the topic.
+    fn schema() -> SchemaRef;
+}
+
+/// A wrapper for Arrow record batches used with typed eCAL pub/sub.
+///
+/// `S` ties the message to the schema announced for the topic. Received
+/// batches whose fields do not match `S::schema()` are dropped.
+#[derive(Debug, Clone)]
+pub struct ArrowMessage<S> {
+    pub batch: RecordBatch,
+    _schema: PhantomData<S>,
+}
+
+impl<S: ArrowSchema> ArrowMessage<S> {
+    /// Wraps a record batch.
+    pub fn new(batch: RecordBatch) -> Self {
+        Self {
+            batch,
+            _schema: PhantomData,
+        }
+    }
+
+    /// Returns a [`PayloadWriter`] for `TypedPublisher::send_payload_writer`.
+    ///
+    /// The batch is encoded here, as by `to_bytes`, and the writer copies the
+    /// encoded stream into the eCAL send buffer. It does not encode in place,
+    /// as the size of an IPC stream is only known after encoding it.
+    ///
+    /// # Errors
+    /// Returns an [`ArrowError`] if the batch does not match `S::schema()` or
+    /// cannot be encoded.
+    pub fn payload_writer(&self) -> Result<ArrowPayloadWriter, ArrowError> {
+        Ok(ArrowPayloadWriter {
+            bytes: self.encode()?,
+        })
+    }
+
+    /// Encodes the batch as a complete Arrow IPC stream.
+    fn encode(&self) -> Result<Vec<u8>, ArrowError> {
+        if self.batch.schema().fields() != S::schema().fields() {
+            return Err(ArrowError::SchemaError(format!(
+                "record batch does not match the schema of {}",
+                S::TYPE_NAME
+            )));
+        }
+
+        let mut buf = Vec::new();
+        let mut stream = StreamWriter::try_new(&mut buf, &self.batch.schema())?;
+        stream.write(&self.batch)?;
+        stream.finish()?;
+        drop(stream);
+        Ok(buf)
+    }
+}
+
+impl<S: ArrowSchema> SubscriberMessage<'_> for ArrowMessage<S> {
+    /// Returns metadata used by eCAL to describe the Arrow data.
+    ///
+    /// This includes:
+    /// - `arrow` as encoding
+    /// - `S::TYPE_NAME` as type name
+    /// - the schema, encoded as Arrow IPC stream, as descriptor
+    fn datatype() -> DataTypeInfo {
+        let mut descriptor = Vec::new();
+        if let Ok(mut stream) = StreamWriter::try_new(&mut descriptor, &S::schema()) {
+            let _ = stream.finish();
+        }
+
+        DataTypeInfo {
+            encoding: "arrow".to_string(),
+            type_name: S::TYPE_NAME.to_string(),
+            descriptor,
+        }
+    }
+
+    /// Decodes the first record batch of the received IPC stream.
+    ///
+    /// # Returns
+    /// - `Some(ArrowMessage<S>)` on success
+    /// - `None` if decoding fails or the batch does not match `S::schema()`
+    fn from_bytes(bytes: &[u8], _data_type_info: &DataTypeInfo) -> Option<Self> {
+        let mut reader = StreamReader::try_new(bytes, None).ok()?;
+        let batch = reader.next()?.ok()?;
+        if batch.schema().fields() != S::schema().fields() {
+            return None;
+        }
+        Some(Self::new(batch))
+    }
+}
+
+impl<S: ArrowSchema> PublisherMessage for ArrowMessage<S> {
+    /// Returns the same datatype information as [`SubscriberMessage`]
+    /// implementation.
+    fn datatype() -> DataTypeInfo {
+        <ArrowMessage<S> as SubscriberMessage>::datatype()
+    }
+
+    /// Encodes the batch as an Arrow IPC stream.
+    ///
+    /// # Errors
+    /// Returns a [`SerializationError`] if the batch does not match
+    /// `S::schema()` or Arrow fails to encode it.
+    fn to_bytes(&self) -> Result<Arc<[u8]>, SerializationError> {
+        let buf = self.encode().map_err(SerializationError::new)?;
+        Ok(Arc::from(buf))
+    }
+}
+
+/// Copies an encoded record batch into the eCAL send buffer.
+///
+/// Created by [`ArrowMessage::payload_writer`].
+pub struct ArrowPayloadWriter {
+    bytes: Vec<u8>,
+}
+
+impl PayloadWriter for ArrowPayloadWriter {
+    fn write_full(&mut self, buf: &mut [u8]) -> bool {
+        let Some(target) = buf.get_mut(..self.bytes.len()) else {
+            return false;
+        };
+        target.copy_from_slice(&self.bytes);
+        true
+    }
+
+    fn get_size(&self) -> usize {
+        self.bytes.len()
+    }
+}
@@ -0,0 +1,92 @@
+use arrow_array::{Float32Array, Int64Array, RecordBatch};
+use arrow_ipc::reader::StreamReader;
+use arrow_ipc::writer::StreamWriter;
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use rustecal_pubsub::{PayloadWriter, PublisherMessage, SubscriberMessage};
+use rustecal_types_arrow::{ArrowMessage, ArrowSchema};
+use std::sync::Arc;
+
+struct Imu;
+
+impl ArrowSchema for Imu {
+    const TYPE_NAME: &'static str = "sensors.Imu";
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("timestamp", DataType::Int64, false),
+            Field::new("acc_x", DataType::Float32, false),
+        ]))
+    }
+}
+
+fn batch() -> RecordBatch {
+    RecordBatch::try_new(
+        Imu::schema(),
+        vec![
+            Arc::new(Int64Array::from(vec![1, 2, 3])),
+            Arc::new(Float32Array::from(vec![0.1, 0.2, 0.3])),
+        ],
+    )
+    .unwrap()
+}
+
+#[test]
+fn datatype() {
+    let info = <ArrowMessage<Imu> as PublisherMessage>::datatype();
+    assert_eq!(info.encoding, "arrow");
+    assert_eq!(info.type_name, "sensors.Imu");
+
+    let reader = StreamReader::try_new(&info.descriptor[..], None).unwrap();
+    assert_eq!(reader.schema(), Imu::schema());
+}
+
+#[test]
+fn round_trip() {
+    let message = ArrowMessage::<Imu>::new(batch());
+    let bytes = message.to_bytes().unwrap();
+
+    let info = <ArrowMessage<Imu> as SubscriberMessage>::datatype();
+    let received = <ArrowMessage<Imu> as SubscriberMessage>::from_bytes(&bytes, &info).unwrap();
+    assert_eq!(received.batch, batch());
+}
+
+#[test]
+fn payload_writer_matches_to_bytes() {
+    let message = ArrowMessage::<Imu>::new(batch());
+    let bytes = message.to_bytes().unwrap();
+
+    let mut writer = message.payload_writer().unwrap();
+    assert_eq!(writer.get_size(), bytes.len());
+
+    let mut buf = vec![0u8; writer.get_size()];
+    assert!(writer.write_full(&mut buf));
+    assert_eq!(&buf[..], &bytes[..]);
+}
+
+fn other_batch() -> RecordBatch {
+    RecordBatch::try_new(
+        Arc::new(Schema::new(vec![Field::new("x", DataType::Int64, false)])),
+        vec![Arc::new(Int64Array::from(vec![1]))],
+    )
+    .unwrap()
+}
+
+#[test]
+fn mismatching_schema_is_not_sent() {
+    let message = ArrowMessage::<Imu>::new(other_batch());
+    assert!(message.to_bytes().is_err());
+    assert!(message.payload_writer().is_err());
+}
+
+#[test]
+fn mismatching_schema_is_rejected() {
+    let other = other_batch();
+    let mut bytes = Vec::new();
+    let mut stream = StreamWriter::try_new(&mut bytes, &other.schema()).unwrap();
+    stream.write(&other).unwrap();
+    stream.finish().unwrap();
+    drop(stream);
+
+    let info = <ArrowMessage<Imu> as SubscriberMessage>::datatype();
+    assert!(<ArrowMessage<Imu> as SubscriberMessage>::from_bytes(&bytes, &info).is_none());
+}
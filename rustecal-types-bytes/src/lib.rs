@@ -3,6 +3,7 @@
 //! Provides support for sending and receiving raw binary messages (`Vec<u8>`) with rustecal.
 
 use rustecal_core::types::DataTypeInfo;
+use rustecal_pubsub::error::SerializationError;
 use rustecal_pubsub::typed_publisher::PublisherMessage;
 use rustecal_pubsub::typed_subscriber::SubscriberMessage;
 use std::{borrow::Cow, sync::Arc};
@@ -59,11 +60,11 @@ impl<'a> PublisherMessage for BytesMessage<'a> {
     /// can hand off the shared memory.  Note: this does copy *once*
     /// into a fresh Arc; if you’re doing *true* zero‐copy send,
     /// you’d use the PayloadWriter API instead of this path.
-    fn to_bytes(&self) -> Arc<[u8]> {
+    fn to_bytes(&self) -> Result<Arc<[u8]>, SerializationError> {
         // if we’re already owned, reuse; otherwise clone the borrowed slice
-        match &self.data {
+        Ok(match &self.data {
             Cow::Owned(vec) => Arc::from(&vec[..]),
             Cow::Borrowed(s) => Arc::from(*s),
-        }
+        })
     }
 }
@@ -0,0 +1,166 @@
+//! # rustecal-types-capnp
+//!
+//! Provides support for Cap'n Proto messages with rustecal.
+//!
+//! - [`CapnpBuilder<T>`] publishes a message built with a Cap'n Proto builder.
+//! - [`CapnpReader<'a, T>`] receives a message and reads its segments directly
+//!   from the buffer handed over by eCAL, without copying.
+//!
+//! `T` is the `Owned` marker type generated by `capnpc` for a struct,
+//! e.g. `person::Owned`.
+
+use capnp::introspect::{Introspect, TypeVariant};
+use capnp::message::{self, Allocator, HeapAllocator, ReaderOptions, TypedBuilder, TypedReader};
+use capnp::schema::StructSchema;
+use capnp::serialize::{self, NoAllocSliceSegments};
+use capnp::traits::Owned;
+use rustecal_core::types::DataTypeInfo;
+use rustecal_pubsub::error::SerializationError;
+use rustecal_pubsub::typed_publisher::PublisherMessage;
+use rustecal_pubsub::typed_subscriber::SubscriberMessage;
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+/// Builds the eCAL metadata for the Cap'n Proto type `T`.
+///
+/// This includes:
+/// - `capnp` as encoding
+/// - the short display name of the struct (e.g. `Person`)
+/// - the schema node of the struct, serialized as a Cap'n Proto message
+fn datatype<T: Introspect>() -> DataTypeInfo {
+    let (type_name, descriptor) = match T::introspect().which() {
+        TypeVariant::Struct(raw) => {
+            let node = StructSchema::new(raw).get_proto();
+            let display_name = node
+                .get_display_name()
+                .ok()
+                .and_then(|name| name.to_string().ok())
+                .unwrap_or_default();
+            let prefix_length = node.get_display_name_prefix_length() as usize;
+            let type_name = display_name
+                .get(prefix_length..)
+                .unwrap_or_default()
+                .to_string();
+
+            let mut schema = message::Builder::new_default();
+            let descriptor = match schema.set_root(node) {
+                Ok(()) => serialize::write_message_to_words(&schema),
+                Err(_) => Vec::new(),
+            };
+            (type_name, descriptor)
+        }
+        _ => (String::new(), Vec::new()),
+    };
+
+    DataTypeInfo {
+        encoding: "capnp".to_string(),
+        type_name,
+        descriptor,
+    }
+}
+
+/// A Cap'n Proto message builder used with `TypedPublisher`.
+///
+/// Derefs to the wrapped [`TypedBuilder`], so `init_root()` and friends can be
+/// called directly.
+pub struct CapnpBuilder<T: Owned, A: Allocator = HeapAllocator> {
+    pub message: TypedBuilder<T, A>,
+}
+
+impl<T: Owned> CapnpBuilder<T> {
+    /// Creates an empty message using the default heap allocator.
+    pub fn new_default() -> Self {
+        Self {
+            message: TypedBuilder::new_default(),
+        }
+    }
+}
+
+impl<T: Owned> Default for CapnpBuilder<T> {
+    fn default() -> Self {
+        Self::new_default()
+    }
+}
+
+impl<T: Owned, A: Allocator> From<TypedBuilder<T, A>> for CapnpBuilder<T, A> {
+    fn from(message: TypedBuilder<T, A>) -> Self {
+        Self { message }
+    }
+}
+
+impl<T: Owned, A: Allocator> Deref for CapnpBuilder<T, A> {
+    type Target = TypedBuilder<T, A>;
+    fn deref(&self) -> &Self::Target {
+        &self.message
+    }
+}
+
+impl<T: Owned, A: Allocator> DerefMut for CapnpBuilder<T, A> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.message
+    }
+}
+
+impl<T, A> PublisherMessage for CapnpBuilder<T, A>
+where
+    T: Owned + Introspect,
+    A: Allocator,
+{
+    fn datatype() -> DataTypeInfo {
+        datatype::<T>()
+    }
+
+    /// Serializes the message, including its segment table.
+    fn to_bytes(&self) -> Result<Arc<[u8]>, SerializationError> {
+        Ok(Arc::from(serialize::write_message_to_words(
+            self.message.borrow_inner(),
+        )))
+    }
+}
+
+/// A received Cap'n Proto message, reading its segments from the borrowed
+/// receive buffer.
+///
+/// Use [`CapnpReader::get`] to access the root struct.
+pub struct CapnpReader<'a, T: Owned> {
+    message: TypedReader<NoAllocSliceSegments<'a>, T>,
+}
+
+impl<'a, T: Owned> CapnpReader<'a, T> {
+    /// Reads a serialized message (including its segment table) from `bytes`
+    /// without copying.
+    ///
+    /// # Errors
+    /// Returns a [`capnp::Error`] if the segment table is malformed or the
+    /// root pointer is not a `T`.
+    pub fn from_slice(mut bytes: &'a [u8]) -> capnp::Result<Self> {
+        let reader =
+            serialize::read_message_from_flat_slice_no_alloc(&mut bytes, ReaderOptions::new())?;
+        let message = TypedReader::new(reader);
+        message.get()?;
+        Ok(Self { message })
+    }
+
+    /// Returns the root struct reader.
+    pub fn get(&self) -> capnp::Result<T::Reader<'_>> {
+        self.message.get()
+    }
+}
+
+impl<'a, T> SubscriberMessage<'a> for CapnpReader<'a, T>
+where
+    T: Owned + Introspect,
+{
+    fn datatype() -> DataTypeInfo {
+        datatype::<T>()
+    }
+
+    /// Reads the message in place from the shared-memory slice.
+    ///
+    /// # Returns
+    /// - `Some(CapnpReader<T>)` if the segment table and root pointer are valid
+    /// - `None` otherwise
+    fn from_bytes(bytes: &'a [u8], _data_type_info: &DataTypeInfo) -> Option<Self> {
+        Self::from_slice(bytes).ok()
+    }
+}
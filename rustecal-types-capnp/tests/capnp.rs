@@ -0,0 +1,52 @@
+use capnp::schema_capnp::node;
+use capnp::traits::HasTypeId;
+use rustecal_pubsub::{PublisherMessage, SubscriberMessage};
+use rustecal_types_capnp::{CapnpBuilder, CapnpReader};
+
+// `schema.capnp` types ship with the capnp runtime, so they serve as test messages.
+
+#[test]
+fn datatype() {
+    let info = <CapnpBuilder<node::Owned> as PublisherMessage>::datatype();
+    assert_eq!(info.encoding, "capnp");
+    assert_eq!(info.type_name, "Node");
+
+    // descriptor is the schema node of the struct
+    let mut bytes = &info.descriptor[..];
+    let schema =
+        capnp::serialize::read_message_from_flat_slice(&mut bytes, Default::default()).unwrap();
+    let schema_node: node::Reader = schema.get_root().unwrap();
+    assert_eq!(schema_node.get_id(), <node::Reader as HasTypeId>::TYPE_ID);
+
+    let sub_info = <CapnpReader<node::Owned> as SubscriberMessage>::datatype();
+    assert_eq!(sub_info.type_name, info.type_name);
+    assert_eq!(sub_info.descriptor, info.descriptor);
+}
+
+#[test]
+fn builder_round_trip() {
+    let mut message = CapnpBuilder::<node::Owned>::new_default();
+    {
+        let mut root = message.init_root();
+        root.set_id(42);
+        root.set_display_name("example.capnp:Sample");
+    }
+    let bytes = message.to_bytes().unwrap();
+
+    let info = <CapnpReader<node::Owned> as SubscriberMessage>::datatype();
+    let received = <CapnpReader<node::Owned> as SubscriberMessage>::from_bytes(&bytes, &info)
+        .expect("valid message");
+    let root = received.get().unwrap();
+    assert_eq!(root.get_id(), 42);
+    assert_eq!(
+        root.get_display_name().unwrap().to_str().unwrap(),
+        "example.capnp:Sample"
+    );
+}
+
+#[test]
+fn invalid_message_is_rejected() {
+    let info = <CapnpReader<node::Owned> as SubscriberMessage>::datatype();
+    let garbage = [0xffu8; 5];
+    assert!(<CapnpReader<node::Owned> as SubscriberMessage>::from_bytes(&garbage, &info).is_none());
+}
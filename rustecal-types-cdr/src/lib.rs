@@ -0,0 +1,131 @@
+//! # rustecal-types-cdr
+//!
+//! Provides support for CDR encoded messages with rustecal, the wire format
+//! used by ROS 2 and DDS.
+//!
+//! Payloads are plain CDR (XCDR1) with the 4-byte encapsulation header, i.e.
+//! exactly what a ROS 2 publisher puts on the wire, so a bridge can forward
+//! them unchanged. The descriptor carries the ROS message definition in
+//! `.msg` syntax.
+
+use cdr::{CdrBe, CdrLe, Infinite};
+use rustecal_core::types::DataTypeInfo;
+use rustecal_pubsub::error::SerializationError;
+use rustecal_pubsub::typed_publisher::PublisherMessage;
+use rustecal_pubsub::typed_subscriber::SubscriberMessage;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Trait to opt-in a Serde type as ROS 2 message.
+///
+/// ```ignore
+/// impl RosMessage for Point {
+///     const TYPE_NAME: &'static str = "geometry_msgs/msg/Point";
+///     const DEFINITION: &'static str = "float64 x\nfloat64 y\nfloat64 z\n";
+/// }
+/// ```
+///
+/// Field order and types of the Serde type must match the definition, as
+/// CDR carries no field names.
+pub trait RosMessage {
+    /// ROS 2 type name, e.g. `"geometry_msgs/msg/Point"`.
+    const TYPE_NAME: &'static str;
+
+    /// Message definition in `.msg` syntax, sent as descriptor.
+    ///
+    /// Definitions of nested types are appended as in rosbag2/MCAP `ros2msg`
+    /// schemas, each preceded by a `===` separator line and `MSG: <type>`.
+    const DEFINITION: &'static str;
+}
+
+/// Byte order of an encoded CDR payload.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Endianness {
+    /// `CDR_LE` encapsulation, the ROS 2 default.
+    #[default]
+    Little,
+    /// `CDR_BE` encapsulation.
+    Big,
+}
+
+/// A wrapper for CDR encoded Serde messages used with typed eCAL pub/sub.
+#[derive(Debug, Clone)]
+pub struct CdrMessage<T> {
+    /// The inner payload.
+    pub data: Arc<T>,
+    /// Byte order used on send, or found in the encapsulation header on receive.
+    pub endianness: Endianness,
+}
+
+impl<T> CdrMessage<T> {
+    /// Creates a new little endian message with given payload.
+    pub fn new(payload: T) -> Self {
+        Self {
+            data: Arc::new(payload),
+            endianness: Endianness::Little,
+        }
+    }
+
+    /// Sets the byte order used on send.
+    pub fn with_endianness(mut self, endianness: Endianness) -> Self {
+        self.endianness = endianness;
+        self
+    }
+}
+
+impl<T> PublisherMessage for CdrMessage<T>
+where
+    T: Serialize + for<'de> Deserialize<'de> + RosMessage,
+{
+    /// Returns metadata used by eCAL to describe the CDR type.
+    ///
+    /// This includes:
+    /// - `cdr` as encoding
+    /// - the ROS 2 type name
+    /// - the `.msg` definition as descriptor
+    fn datatype() -> DataTypeInfo {
+        DataTypeInfo {
+            encoding: "cdr".to_string(),
+            type_name: T::TYPE_NAME.to_string(),
+            descriptor: T::DEFINITION.as_bytes().to_vec(),
+        }
+    }
+
+    /// Encodes the payload, prefixed with the encapsulation header.
+    ///
+    /// # Errors
+    /// Returns a [`SerializationError`] if the payload uses a Serde feature
+    /// CDR cannot represent, e.g. maps or sequences of unknown length.
+    fn to_bytes(&self) -> Result<Arc<[u8]>, SerializationError> {
+        let bytes = match self.endianness {
+            Endianness::Little => cdr::serialize::<_, _, CdrLe>(&*self.data, Infinite),
+            Endianness::Big => cdr::serialize::<_, _, CdrBe>(&*self.data, Infinite),
+        }
+        .map_err(SerializationError::new)?;
+        Ok(Arc::from(bytes))
+    }
+}
+
+impl<T> SubscriberMessage<'_> for CdrMessage<T>
+where
+    T: Serialize + for<'de> Deserialize<'de> + RosMessage,
+{
+    fn datatype() -> DataTypeInfo {
+        <CdrMessage<T> as PublisherMessage>::datatype()
+    }
+
+    /// Decodes the payload, honoring the byte order of the encapsulation header.
+    ///
+    /// # Returns
+    /// - `Some(CdrMessage<T>)` on success
+    /// - `None` if the header is unknown or decoding fails
+    fn from_bytes(bytes: &[u8], _data_type_info: &DataTypeInfo) -> Option<Self> {
+        let endianness = match bytes.get(..2)? {
+            [0x00, 0x01] => Endianness::Little,
+            [0x00, 0x00] => Endianness::Big,
+            _ => return None,
+        };
+        let payload = cdr::deserialize::<T>(bytes).ok()?;
+        Some(Self::new(payload).with_endianness(endianness))
+    }
+}
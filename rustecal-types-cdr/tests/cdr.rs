@@ -0,0 +1,76 @@
+use rustecal_pubsub::{PublisherMessage, SubscriberMessage};
+use rustecal_types_cdr::{CdrMessage, Endianness, RosMessage};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct Point {
+    x: f64,
+    y: f64,
+    z: f64,
+}
+
+impl RosMessage for Point {
+    const TYPE_NAME: &'static str = "geometry_msgs/msg/Point";
+    const DEFINITION: &'static str = "float64 x\nfloat64 y\nfloat64 z\n";
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct Text {
+    data: String,
+}
+
+impl RosMessage for Text {
+    const TYPE_NAME: &'static str = "std_msgs/msg/String";
+    const DEFINITION: &'static str = "string data\n";
+}
+
+#[test]
+fn datatype() {
+    let info = <CdrMessage<Point> as PublisherMessage>::datatype();
+    assert_eq!(info.encoding, "cdr");
+    assert_eq!(info.type_name, "geometry_msgs/msg/Point");
+    assert_eq!(info.descriptor, b"float64 x\nfloat64 y\nfloat64 z\n");
+}
+
+#[test]
+fn matches_ros2_wire_format() {
+    // bytes as published by a ROS 2 node for std_msgs/msg/String { data: "hi" }
+    let ros2 = [
+        0x00, 0x01, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, b'h', b'i', 0x00,
+    ];
+
+    let message = CdrMessage::new(Text { data: "hi".into() });
+    assert_eq!(&message.to_bytes().unwrap()[..], &ros2[..]);
+
+    let info = <CdrMessage<Text> as SubscriberMessage>::datatype();
+    let received = <CdrMessage<Text> as SubscriberMessage>::from_bytes(&ros2, &info).unwrap();
+    assert_eq!(received.data.data, "hi");
+}
+
+#[test]
+fn round_trip_both_endiannesses() {
+    let point = Point {
+        x: 1.0,
+        y: 2.0,
+        z: 3.0,
+    };
+    let info = <CdrMessage<Point> as SubscriberMessage>::datatype();
+
+    for endianness in [Endianness::Little, Endianness::Big] {
+        let bytes = CdrMessage::new(point.clone())
+            .with_endianness(endianness)
+            .to_bytes()
+            .unwrap();
+        let received = <CdrMessage<Point> as SubscriberMessage>::from_bytes(&bytes, &info).unwrap();
+        assert_eq!(*received.data, point);
+        assert_eq!(received.endianness, endianness);
+    }
+}
+
+#[test]
+fn unknown_encapsulation_is_rejected() {
+    let info = <CdrMessage<Point> as SubscriberMessage>::datatype();
+    // PL_CDR_LE, not supported for plain Serde types
+    let bytes = [0x00, 0x03, 0x00, 0x00];
+    assert!(<CdrMessage<Point> as SubscriberMessage>::from_bytes(&bytes, &info).is_none());
+}
@@ -0,0 +1,221 @@
+//! # rustecal-types-compressed
+//!
+//! Provides a compression adapter for any rustecal message type.
+//!
+//! [`Compressed<M, C>`] compresses the serialized bytes of `M` with the codec `C`
+//! on send and decompresses them on receive. The codec is appended to the
+//! encoding (e.g. `proto+zstd`), while type name and descriptor of `M` are kept,
+//! so tools still see the original type.
+//!
+//! Decompressed payloads are limited to [`DEFAULT_MAX_DECOMPRESSED_SIZE`]
+//! bytes, so a small malicious payload cannot expand to exhaust the
+//! subscriber's memory. The limit is part of the message type, see
+//! [`Compressed`].
+//!
+//! ## Features
+//!
+//! - `zstd` (default): [`Zstd`] codec
+//! - `lz4` (default): [`Lz4`] codec (LZ4 frame format)
+
+use rustecal_core::types::DataTypeInfo;
+use rustecal_pubsub::error::SerializationError;
+use rustecal_pubsub::typed_publisher::{PublisherMessage, SendContext};
+use rustecal_pubsub::typed_subscriber::{ReceiveContext, SubscriberMessage};
+use std::io::Read;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// Default limit of decompressed payloads, 64 MiB.
+pub const DEFAULT_MAX_DECOMPRESSED_SIZE: usize = 64 * 1024 * 1024;
+
+/// Reads `reader` to the end, failing if it yields more than `limit` bytes.
+fn read_limited(reader: impl Read, limit: usize) -> Option<Vec<u8>> {
+    let mut decoded = Vec::new();
+    reader
+        .take(limit as u64 + 1)
+        .read_to_end(&mut decoded)
+        .ok()?;
+    (decoded.len() <= limit).then_some(decoded)
+}
+
+/// A compression algorithm usable with [`Compressed`].
+pub trait Codec {
+    /// Suffix appended to the inner encoding, e.g. `"zstd"` for `proto+zstd`.
+    const NAME: &'static str;
+
+    /// Compresses `bytes`.
+    fn compress(bytes: &[u8]) -> Result<Vec<u8>, SerializationError>;
+
+    /// Decompresses `bytes`, returning `None` if they are not valid for this
+    /// codec or decompress to more than `limit` bytes.
+    fn decompress(bytes: &[u8], limit: usize) -> Option<Vec<u8>>;
+}
+
+/// Zstandard compression at the default level.
+#[cfg(feature = "zstd")]
+#[derive(Debug, Clone, Copy)]
+pub struct Zstd;
+
+#[cfg(feature = "zstd")]
+impl Codec for Zstd {
+    const NAME: &'static str = "zstd";
+
+    fn compress(bytes: &[u8]) -> Result<Vec<u8>, SerializationError> {
+        zstd::bulk::compress(bytes, zstd::DEFAULT_COMPRESSION_LEVEL)
+            .map_err(SerializationError::new)
+    }
+
+    fn decompress(bytes: &[u8], limit: usize) -> Option<Vec<u8>> {
+        read_limited(zstd::stream::read::Decoder::new(bytes).ok()?, limit)
+    }
+}
+
+/// LZ4 compression using the LZ4 frame format.
+#[cfg(feature = "lz4")]
+#[derive(Debug, Clone, Copy)]
+pub struct Lz4;
+
+#[cfg(feature = "lz4")]
+impl Codec for Lz4 {
+    const NAME: &'static str = "lz4";
+
+    fn compress(bytes: &[u8]) -> Result<Vec<u8>, SerializationError> {
+        use std::io::Write;
+
+        let mut encoder = lz4_flex::frame::FrameEncoder::new(Vec::new());
+        encoder.write_all(bytes).map_err(SerializationError::new)?;
+        encoder.finish().map_err(SerializationError::new)
+    }
+
+    fn decompress(bytes: &[u8], limit: usize) -> Option<Vec<u8>> {
+        read_limited(lz4_flex::frame::FrameDecoder::new(bytes), limit)
+    }
+}
+
+/// Wraps a message and compresses its payload with the codec `C`.
+///
+/// Derefs to the inner message, so `received.payload.data` keeps working.
+///
+/// ```ignore
+/// let publisher = TypedPublisher::<Compressed<ProtobufMessage<Person>, Zstd>>::new("person")?;
+/// publisher.send(&Compressed::new(message), Timestamp::Auto);
+/// ```
+///
+/// Received payloads decompressing to more than `LIMIT` bytes are dropped.
+/// Subscribers expecting larger messages raise it for their topic only:
+///
+/// ```ignore
+/// const MAX_IMAGE: usize = 256 * 1024 * 1024;
+/// let subscriber = TypedSubscriber::<Compressed<ProtobufMessage<Image>, Zstd, MAX_IMAGE>>::new("image")?;
+/// ```
+///
+/// On receive, `M` decodes from a temporary decompression buffer, so only
+/// owning message types (e.g. `ProtobufMessage<T>`, `JsonMessage<T>`) can be
+/// wrapped, not borrowing ones like `BytesMessage<'a>`.
+#[derive(Debug, Clone)]
+pub struct Compressed<M, C, const LIMIT: usize = DEFAULT_MAX_DECOMPRESSED_SIZE> {
+    /// The wrapped message.
+    pub message: M,
+    _codec: PhantomData<C>,
+}
+
+impl<M, C: Codec, const LIMIT: usize> Compressed<M, C, LIMIT> {
+    /// Maximum size of a decompressed payload in bytes.
+    pub const MAX_DECOMPRESSED_SIZE: usize = LIMIT;
+
+    /// Wraps the given message.
+    pub fn new(message: M) -> Self {
+        Self {
+            message,
+            _codec: PhantomData,
+        }
+    }
+}
+
+impl<M, C: Codec, const LIMIT: usize> From<M> for Compressed<M, C, LIMIT> {
+    fn from(message: M) -> Self {
+        Self::new(message)
+    }
+}
+
+impl<M, C, const LIMIT: usize> Deref for Compressed<M, C, LIMIT> {
+    type Target = M;
+    fn deref(&self) -> &M {
+        &self.message
+    }
+}
+
+/// Removes the codec suffix from the publisher's encoding.
+fn inner_info<C: Codec>(info: &DataTypeInfo) -> DataTypeInfo {
+    let suffix = format!("+{}", C::NAME);
+    DataTypeInfo {
+        encoding: info
+            .encoding
+            .strip_suffix(&suffix)
+            .unwrap_or(&info.encoding)
+            .to_string(),
+        ..info.clone()
+    }
+}
+
+/// Appends the codec name to the encoding of `info`.
+fn compressed_info<C: Codec>(info: DataTypeInfo) -> DataTypeInfo {
+    DataTypeInfo {
+        encoding: format!("{}+{}", info.encoding, C::NAME),
+        ..info
+    }
+}
+
+impl<M, C, const LIMIT: usize> PublisherMessage for Compressed<M, C, LIMIT>
+where
+    M: PublisherMessage,
+    C: Codec,
+{
+    /// Returns the inner message metadata with the codec appended to the encoding.
+    fn datatype() -> DataTypeInfo {
+        compressed_info::<C>(M::datatype())
+    }
+
+    /// Serializes the inner message and compresses the result.
+    fn to_bytes(&self) -> Result<Arc<[u8]>, SerializationError> {
+        let bytes = self.message.to_bytes()?;
+        C::compress(&bytes).map(Arc::from)
+    }
+
+    /// Serializes the inner message with the given context and compresses the result.
+    fn to_bytes_with(&self, context: &SendContext<'_>) -> Result<Arc<[u8]>, SerializationError> {
+        let bytes = self.message.to_bytes_with(context)?;
+        C::compress(&bytes).map(Arc::from)
+    }
+}
+
+impl<M, C, const LIMIT: usize> SubscriberMessage<'_> for Compressed<M, C, LIMIT>
+where
+    M: for<'b> SubscriberMessage<'b>,
+    C: Codec,
+{
+    fn datatype() -> DataTypeInfo {
+        compressed_info::<C>(<M as SubscriberMessage>::datatype())
+    }
+
+    /// Decompresses the payload and decodes the inner message.
+    ///
+    /// The inner message sees the publisher's metadata with the codec suffix
+    /// removed from the encoding. Payloads that fail to decompress or exceed
+    /// `LIMIT` bytes are dropped.
+    fn from_bytes(bytes: &[u8], data_type_info: &DataTypeInfo) -> Option<Self> {
+        let decompressed = C::decompress(bytes, LIMIT)?;
+        M::from_bytes(&decompressed, &inner_info::<C>(data_type_info)).map(Self::new)
+    }
+
+    /// Like [`from_bytes`](Self::from_bytes), passing the context on to the inner message.
+    fn from_bytes_with(
+        bytes: &[u8],
+        data_type_info: &DataTypeInfo,
+        context: &ReceiveContext<'_>,
+    ) -> Option<Self> {
+        let decompressed = C::decompress(bytes, LIMIT)?;
+        M::from_bytes_with(&decompressed, &inner_info::<C>(data_type_info), context).map(Self::new)
+    }
+}
@@ -0,0 +1,106 @@
+#![cfg(all(feature = "zstd", feature = "lz4"))]
+
+use rustecal_pubsub::{PublisherMessage, SubscriberMessage};
+use rustecal_types_compressed::{Codec, Compressed, DEFAULT_MAX_DECOMPRESSED_SIZE, Lz4, Zstd};
+use rustecal_types_serde::JsonMessage;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct Sample {
+    values: Vec<u32>,
+}
+
+fn sample() -> Sample {
+    Sample {
+        values: vec![7; 1000],
+    }
+}
+
+fn round_trip<C: Codec>() {
+    let message = Compressed::<_, C>::new(JsonMessage::new(sample()));
+    let bytes = message.to_bytes().unwrap();
+    assert!(bytes.len() < message.message.to_bytes().unwrap().len());
+
+    let info = <Compressed<JsonMessage<Sample>, C> as SubscriberMessage>::datatype();
+    let received =
+        <Compressed<JsonMessage<Sample>, C> as SubscriberMessage>::from_bytes(&bytes, &info)
+            .unwrap();
+    assert_eq!(*received.data, sample());
+}
+
+#[test]
+fn datatype_keeps_inner_type() {
+    let inner = <JsonMessage<Sample> as PublisherMessage>::datatype();
+    let info = <Compressed<JsonMessage<Sample>, Zstd> as PublisherMessage>::datatype();
+    assert_eq!(info.encoding, "json+zstd");
+    assert_eq!(info.type_name, inner.type_name);
+    assert_eq!(info.descriptor, inner.descriptor);
+
+    let info = <Compressed<JsonMessage<Sample>, Lz4> as PublisherMessage>::datatype();
+    assert_eq!(info.encoding, "json+lz4");
+}
+
+#[test]
+fn zstd_round_trip() {
+    round_trip::<Zstd>();
+}
+
+#[test]
+fn lz4_round_trip() {
+    round_trip::<Lz4>();
+}
+
+#[test]
+fn uncompressed_payload_is_dropped() {
+    let bytes = JsonMessage::new(sample()).to_bytes().unwrap();
+    let info = <Compressed<JsonMessage<Sample>, Zstd> as SubscriberMessage>::datatype();
+    assert!(
+        <Compressed<JsonMessage<Sample>, Zstd> as SubscriberMessage>::from_bytes(&bytes, &info)
+            .is_none()
+    );
+}
+
+fn bomb_is_rejected<C: Codec>() {
+    // a few KB expanding beyond the default limit
+    let bomb = C::compress(&vec![0; DEFAULT_MAX_DECOMPRESSED_SIZE + 1]).unwrap();
+    assert!(bomb.len() < 1024 * 1024);
+    assert_eq!(
+        Compressed::<JsonMessage<Sample>, C>::MAX_DECOMPRESSED_SIZE,
+        DEFAULT_MAX_DECOMPRESSED_SIZE
+    );
+
+    let info = <Compressed<JsonMessage<Sample>, C> as SubscriberMessage>::datatype();
+    assert!(
+        <Compressed<JsonMessage<Sample>, C> as SubscriberMessage>::from_bytes(&bomb, &info)
+            .is_none()
+    );
+
+    let small = C::compress(&[1; 100]).unwrap();
+    assert_eq!(C::decompress(&small, 100).unwrap().len(), 100);
+    assert!(C::decompress(&small, 99).is_none());
+}
+
+#[test]
+fn zstd_bomb_is_rejected() {
+    bomb_is_rejected::<Zstd>();
+}
+
+#[test]
+fn lz4_bomb_is_rejected() {
+    bomb_is_rejected::<Lz4>();
+}
+
+#[test]
+fn limit_is_set_per_type() {
+    type Small = Compressed<JsonMessage<Sample>, Zstd, 100>;
+
+    let bytes = Compressed::<_, Zstd>::new(JsonMessage::new(sample()))
+        .to_bytes()
+        .unwrap();
+    let info = <Small as SubscriberMessage>::datatype();
+    assert!(<Small as SubscriberMessage>::from_bytes(&bytes, &info).is_none());
+    assert!(
+        <Compressed<JsonMessage<Sample>, Zstd> as SubscriberMessage>::from_bytes(&bytes, &info)
+            .is_some()
+    );
+}
@@ -0,0 +1,132 @@
+//! # rustecal-types-flatbuffers
+//!
+//! Provides support for FlatBuffers messages with rustecal.
+//!
+//! On receive, the root table is verified and read directly from the
+//! shared-memory slice handed over by eCAL, without copying or decoding.
+
+use flatbuffers::{Allocator, FlatBufferBuilder, Follow, InvalidFlatbuffer, Verifiable};
+use rustecal_core::types::DataTypeInfo;
+use rustecal_pubsub::error::SerializationError;
+use rustecal_pubsub::typed_publisher::PublisherMessage;
+use rustecal_pubsub::typed_subscriber::SubscriberMessage;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// Trait to opt-in a generated FlatBuffers table for use with eCAL.
+///
+/// Provides the metadata `flatc` does not attach to the generated Rust types.
+///
+/// ```ignore
+/// impl IsFlatbufferType for Monster<'_> {
+///     const TYPE_NAME: &'static str = "MyGame.Sample.Monster";
+///     const SCHEMA: &'static [u8] = include_bytes!("../schemas/monster.bfbs");
+/// }
+/// ```
+pub trait IsFlatbufferType {
+    /// Fully qualified table name, e.g. `"MyGame.Sample.Monster"`.
+    const TYPE_NAME: &'static str;
+
+    /// Binary reflection schema (`flatc --binary --schema`), sent as descriptor.
+    ///
+    /// Empty by default.
+    const SCHEMA: &'static [u8] = &[];
+}
+
+/// A verified FlatBuffers message used with typed eCAL pub/sub.
+///
+/// Holds the buffer together with its root table `T` (e.g. `Monster<'a>`),
+/// both borrowed for `'a`. Derefs to the root table, so generated accessors
+/// can be called directly on the message.
+#[derive(Clone, Copy)]
+pub struct FlatbufferMessage<'a, T> {
+    data: &'a [u8],
+    root: T,
+}
+
+impl<'a, T> FlatbufferMessage<'a, T>
+where
+    T: Follow<'a, Inner = T> + Verifiable + 'a,
+{
+    /// Verifies `data` and reads its root table.
+    ///
+    /// # Errors
+    /// Returns an [`InvalidFlatbuffer`] if `data` does not contain a valid `T`.
+    pub fn from_slice(data: &'a [u8]) -> Result<Self, InvalidFlatbuffer> {
+        let root = flatbuffers::root::<T>(data)?;
+        Ok(Self { data, root })
+    }
+
+    /// Wraps the finished buffer of `builder` for sending.
+    ///
+    /// # Errors
+    /// Returns an [`InvalidFlatbuffer`] if the builder was not finished with a `T` root.
+    pub fn from_builder<A: Allocator + 'a>(
+        builder: &'a FlatBufferBuilder<'_, A>,
+    ) -> Result<Self, InvalidFlatbuffer> {
+        Self::from_slice(builder.finished_data())
+    }
+}
+
+impl<'a, T> FlatbufferMessage<'a, T> {
+    /// Returns the root table.
+    pub fn root(&self) -> &T {
+        &self.root
+    }
+
+    /// Returns the underlying buffer.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+impl<T> Deref for FlatbufferMessage<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.root
+    }
+}
+
+impl<'a, T> SubscriberMessage<'a> for FlatbufferMessage<'a, T>
+where
+    T: Follow<'a, Inner = T> + Verifiable + IsFlatbufferType + 'a,
+{
+    /// Returns metadata used by eCAL to describe the FlatBuffers type.
+    ///
+    /// This includes:
+    /// - `flatb` as encoding
+    /// - the fully qualified table name
+    /// - the binary reflection schema as descriptor
+    fn datatype() -> DataTypeInfo {
+        DataTypeInfo {
+            encoding: "flatb".to_string(),
+            type_name: T::TYPE_NAME.to_string(),
+            descriptor: T::SCHEMA.to_vec(),
+        }
+    }
+
+    /// Verifies the shared-memory slice and borrows the root table from it.
+    ///
+    /// # Returns
+    /// - `Some(FlatbufferMessage<T>)` if the buffer holds a valid `T`
+    /// - `None` if verification fails
+    fn from_bytes(bytes: &'a [u8], _data_type_info: &DataTypeInfo) -> Option<Self> {
+        Self::from_slice(bytes).ok()
+    }
+}
+
+impl<'a, T> PublisherMessage for FlatbufferMessage<'a, T>
+where
+    T: Follow<'a, Inner = T> + Verifiable + IsFlatbufferType + 'a,
+{
+    /// Returns the same datatype information as [`SubscriberMessage`]
+    /// implementation.
+    fn datatype() -> DataTypeInfo {
+        <FlatbufferMessage<'a, T> as SubscriberMessage<'a>>::datatype()
+    }
+
+    /// Copies the finished buffer into a shared byte buffer.
+    fn to_bytes(&self) -> Result<Arc<[u8]>, SerializationError> {
+        Ok(Arc::from(self.data))
+    }
+}
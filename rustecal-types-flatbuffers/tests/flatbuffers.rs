@@ -0,0 +1,93 @@
+use flatbuffers::{FlatBufferBuilder, Follow, ForwardsUOffset, Verifiable, Verifier, WIPOffset};
+use rustecal_pubsub::{PublisherMessage, SubscriberMessage};
+use rustecal_types_flatbuffers::{FlatbufferMessage, IsFlatbufferType};
+
+// Hand-written equivalent of `flatc --rust` output for:
+//
+//   namespace example;
+//   table Sample { name: string; value: uint; }
+#[derive(Copy, Clone, PartialEq)]
+struct Sample<'a> {
+    _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> Follow<'a> for Sample<'a> {
+    type Inner = Sample<'a>;
+    unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+        Self {
+            _tab: unsafe { flatbuffers::Table::new(buf, loc) },
+        }
+    }
+}
+
+impl<'a> Sample<'a> {
+    const VT_NAME: flatbuffers::VOffsetT = 4;
+    const VT_VALUE: flatbuffers::VOffsetT = 6;
+
+    fn name(&self) -> Option<&'a str> {
+        unsafe { self._tab.get::<ForwardsUOffset<&str>>(Self::VT_NAME, None) }
+    }
+
+    fn value(&self) -> u32 {
+        unsafe { self._tab.get::<u32>(Self::VT_VALUE, Some(0)).unwrap() }
+    }
+}
+
+impl Verifiable for Sample<'_> {
+    fn run_verifier(v: &mut Verifier, pos: usize) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+        v.visit_table(pos)?
+            .visit_field::<ForwardsUOffset<&str>>("name", Self::VT_NAME, false)?
+            .visit_field::<u32>("value", Self::VT_VALUE, false)?
+            .finish();
+        Ok(())
+    }
+}
+
+impl IsFlatbufferType for Sample<'_> {
+    const TYPE_NAME: &'static str = "example.Sample";
+    const SCHEMA: &'static [u8] = b"BFBS";
+}
+
+fn build(name: &str, value: u32) -> FlatBufferBuilder<'static> {
+    let mut fbb = FlatBufferBuilder::new();
+    let name = fbb.create_string(name);
+    let start = fbb.start_table();
+    fbb.push_slot::<u32>(Sample::VT_VALUE, value, 0);
+    fbb.push_slot_always::<WIPOffset<_>>(Sample::VT_NAME, name);
+    let end = fbb.end_table(start);
+    fbb.finish(WIPOffset::<Sample>::new(end.value()), None);
+    fbb
+}
+
+#[test]
+fn datatype() {
+    let info = <FlatbufferMessage<Sample> as PublisherMessage>::datatype();
+    assert_eq!(info.encoding, "flatb");
+    assert_eq!(info.type_name, "example.Sample");
+    assert_eq!(info.descriptor, b"BFBS");
+}
+
+#[test]
+fn builder_round_trip() {
+    let fbb = build("sample", 42);
+    let message = FlatbufferMessage::<Sample>::from_builder(&fbb).unwrap();
+    let bytes = message.to_bytes().unwrap();
+
+    let info = <FlatbufferMessage<Sample> as SubscriberMessage>::datatype();
+    let received = <FlatbufferMessage<Sample> as SubscriberMessage>::from_bytes(&bytes, &info)
+        .expect("valid buffer");
+    assert_eq!(received.name(), Some("sample"));
+    assert_eq!(received.value(), 42);
+
+    // the root table points into the received slice, no copy was made
+    assert_eq!(received.data().as_ptr(), bytes.as_ptr());
+}
+
+#[test]
+fn invalid_buffer_is_rejected() {
+    let info = <FlatbufferMessage<Sample> as SubscriberMessage>::datatype();
+    let garbage = [0xffu8; 3];
+    assert!(
+        <FlatbufferMessage<Sample> as SubscriberMessage>::from_bytes(&garbage, &info).is_none()
+    );
+}
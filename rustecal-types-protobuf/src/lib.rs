@@ -5,6 +5,7 @@
 use prost::Message;
 use prost_reflect::{FileDescriptor, ReflectMessage};
 use rustecal_core::types::DataTypeInfo;
+use rustecal_pubsub::error::SerializationError;
 use rustecal_pubsub::typed_publisher::PublisherMessage;
 use rustecal_pubsub::typed_subscriber::SubscriberMessage;
 use std::collections::HashSet;
@@ -113,15 +114,14 @@ where
 
     /// Encodes the message to a byte buffer.
     ///
-    /// # Panics
-    /// Will panic if `prost::Message::encode` fails (should never panic for
-    /// valid messages).
-    fn to_bytes(&self) -> Arc<[u8]> {
+    /// # Errors
+    /// Returns a [`SerializationError`] if `prost::Message::encode` fails.
+    fn to_bytes(&self) -> Result<Arc<[u8]>, SerializationError> {
         let mut buf = Vec::with_capacity(self.data.encoded_len());
         self.data
             .encode(&mut buf)
-            .expect("Failed to encode protobuf message");
-        Arc::from(buf)
+            .map_err(SerializationError::new)?;
+        Ok(Arc::from(buf))
     }
 }
 
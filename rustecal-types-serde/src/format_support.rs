@@ -1,13 +1,29 @@
+use rustecal_pubsub::error::SerializationError;
+
 /// Defines a serialization format adapter for Serde payloads.
 pub trait FormatSupport {
     /// The encoding label for DataTypeInfo.
     const ENCODING: &'static str;
     /// Serialize the payload to bytes.
-    fn encode<T: serde::Serialize>(payload: &T) -> Vec<u8>;
+    fn encode<T: serde::Serialize>(payload: &T) -> Result<Vec<u8>, SerializationError>;
     /// Deserialize the payload from bytes.
     fn decode<T: for<'de> serde::Deserialize<'de>>(bytes: &[u8]) -> Option<T>;
 }
 
+/// Common view on the format-specific message wrappers generated by [`make_format!`].
+///
+/// Exposes the payload type and the serialization format, so that adapters
+/// (e.g. schema support) can be written once for all formats.
+pub trait SerdeMessage {
+    /// The wrapped Serde payload type.
+    type Payload: serde::Serialize + for<'de> serde::Deserialize<'de> + Clone;
+    /// The serialization format used on the wire.
+    type Format: FormatSupport;
+
+    /// Wraps an already decoded payload.
+    fn from_payload(payload: Self::Payload) -> Self;
+}
+
 /// Helper to extract the short Rust type name without module prefixes.
 pub fn short_type_name<T>() -> String {
     let full = std::any::type_name::<T>();
@@ -37,5 +53,16 @@ macro_rules! make_format {
                 }
             }
         }
+        impl<T> $crate::format_support::SerdeMessage for $msg_type<T>
+        where
+            T: serde::Serialize + for<'de> serde::Deserialize<'de> + Clone,
+        {
+            type Payload = T;
+            type Format = $support;
+
+            fn from_payload(payload: T) -> Self {
+                Self::new(payload)
+            }
+        }
     };
 }
@@ -1,6 +1,7 @@
 use crate::format_support::{FormatSupport, short_type_name};
 use crate::make_format;
 use rustecal_core::types::DataTypeInfo;
+use rustecal_pubsub::error::SerializationError;
 use rustecal_pubsub::typed_publisher::PublisherMessage;
 use rustecal_pubsub::typed_subscriber::SubscriberMessage;
 use serde::{Deserialize, Serialize};
@@ -11,8 +12,8 @@ use std::sync::Arc;
 pub struct JsonSupport;
 impl FormatSupport for JsonSupport {
     const ENCODING: &'static str = "json";
-    fn encode<T: Serialize>(payload: &T) -> Vec<u8> {
-        serde_json::to_vec(payload).expect("JSON serialization failed")
+    fn encode<T: Serialize>(payload: &T) -> Result<Vec<u8>, SerializationError> {
+        serde_json::to_vec(payload).map_err(SerializationError::new)
     }
     fn decode<T: for<'de> Deserialize<'de>>(bytes: &[u8]) -> Option<T> {
         serde_json::from_slice(bytes).ok()
@@ -32,8 +33,8 @@ where
             descriptor: vec![],
         }
     }
-    fn to_bytes(&self) -> Arc<[u8]> {
-        Arc::from(JsonSupport::encode(&*self.data))
+    fn to_bytes(&self) -> Result<Arc<[u8]>, SerializationError> {
+        JsonSupport::encode(&*self.data).map(Arc::from)
     }
 }
 impl<T> SubscriberMessage<'_> for JsonMessage<T>
@@ -1,12 +1,21 @@
 //! # rustecal-types-serde
 //!
 //! eCAL Pub/Sub support for Serde-enabled messages.
+//!
+//! ## Features
+//! - `schema`: adds [`WithSchema`] to publish a JSON Schema of the payload type
+//!   as descriptor (via `schemars`).
+//! - `validate`: validates received payloads against the publisher's schema.
 
 pub mod cbor_message;
 pub mod format_support;
 pub mod json_message;
 pub mod msgpack_message;
+#[cfg(feature = "schema")]
+pub mod schema;
 
 pub use cbor_message::CborMessage;
 pub use json_message::JsonMessage;
 pub use msgpack_message::MsgpackMessage;
+#[cfg(feature = "schema")]
+pub use schema::WithSchema;
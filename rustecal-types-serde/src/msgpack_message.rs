@@ -1,18 +1,24 @@
 use crate::format_support::{FormatSupport, short_type_name};
 use crate::make_format;
 use rustecal_core::types::DataTypeInfo;
+use rustecal_pubsub::error::SerializationError;
 use rustecal_pubsub::typed_publisher::PublisherMessage;
 use rustecal_pubsub::typed_subscriber::SubscriberMessage;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 /// MessagePack support using `rmp-serde`.
+///
+/// Structs are encoded as maps keyed by field name, like in JSON and CBOR, so
+/// other languages can read them without knowing the field order and
+/// [`WithSchema`](crate::WithSchema) can validate them. Structs encoded as
+/// arrays are still accepted on receive.
 #[derive(Debug, Clone)]
 pub struct MsgpackSupport;
 impl FormatSupport for MsgpackSupport {
     const ENCODING: &'static str = "msgpack";
-    fn encode<T: Serialize>(payload: &T) -> Vec<u8> {
-        rmp_serde::to_vec(payload).expect("MessagePack serialization failed")
+    fn encode<T: Serialize>(payload: &T) -> Result<Vec<u8>, SerializationError> {
+        rmp_serde::to_vec_named(payload).map_err(SerializationError::new)
     }
     fn decode<T: for<'de> Deserialize<'de>>(bytes: &[u8]) -> Option<T> {
         rmp_serde::from_slice(bytes).ok()
@@ -32,8 +38,8 @@ where
             descriptor: vec![],
         }
     }
-    fn to_bytes(&self) -> Arc<[u8]> {
-        Arc::from(MsgpackSupport::encode(&*self.data))
+    fn to_bytes(&self) -> Result<Arc<[u8]>, SerializationError> {
+        MsgpackSupport::encode(&*self.data).map(Arc::from)
     }
 }
 impl<T> SubscriberMessage<'_> for MsgpackMessage<T>
@@ -0,0 +1,158 @@
+//! JSON Schema descriptors for Serde message types.
+//!
+//! [`WithSchema`] wraps any of the format-specific messages (`JsonMessage`,
+//! `CborMessage`, `MsgpackMessage`) and publishes a JSON Schema of the payload
+//! type, generated by `schemars`, in `DataTypeInfo.descriptor`. This lets eCAL
+//! Monitor and subscribers written in other languages discover the structure
+//! of the payload.
+//!
+//! With the `validate` feature enabled, a `WithSchema` subscriber also checks
+//! every received payload against the schema announced by the publisher and
+//! drops messages that do not conform. Payloads are checked in their JSON
+//! data model, so CBOR and MessagePack payloads containing byte strings or
+//! map keys other than strings cannot be validated and are dropped as well.
+
+use crate::format_support::SerdeMessage;
+use rustecal_core::types::DataTypeInfo;
+use rustecal_pubsub::error::SerializationError;
+use rustecal_pubsub::typed_publisher::PublisherMessage;
+use rustecal_pubsub::typed_subscriber::SubscriberMessage;
+use schemars::JsonSchema;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// Wraps a Serde message and embeds the JSON Schema of its payload in the descriptor.
+///
+/// Derefs to the inner message, so `received.payload.data` keeps working.
+///
+/// ```ignore
+/// let publisher = TypedPublisher::<WithSchema<JsonMessage<MyData>>>::new("topic")?;
+/// publisher.send(&WithSchema::new(JsonMessage::new(data)), Timestamp::Auto);
+/// ```
+#[derive(Debug, Clone)]
+pub struct WithSchema<M> {
+    /// The wrapped format-specific message.
+    pub message: M,
+}
+
+impl<M> WithSchema<M> {
+    /// Wraps the given message.
+    pub fn new(message: M) -> Self {
+        Self { message }
+    }
+}
+
+impl<M> From<M> for WithSchema<M> {
+    fn from(message: M) -> Self {
+        Self::new(message)
+    }
+}
+
+impl<M> Deref for WithSchema<M> {
+    type Target = M;
+    fn deref(&self) -> &M {
+        &self.message
+    }
+}
+
+/// Returns the JSON Schema of `T`, serialized as JSON, for use as a descriptor.
+pub fn schema_descriptor<T: JsonSchema>() -> Vec<u8> {
+    serde_json::to_vec(&schemars::schema_for!(T)).unwrap_or_default()
+}
+
+impl<M> PublisherMessage for WithSchema<M>
+where
+    M: PublisherMessage + SerdeMessage,
+    M::Payload: JsonSchema,
+{
+    /// Returns the inner message metadata with the JSON Schema as descriptor.
+    fn datatype() -> DataTypeInfo {
+        DataTypeInfo {
+            descriptor: schema_descriptor::<M::Payload>(),
+            ..M::datatype()
+        }
+    }
+
+    fn to_bytes(&self) -> Result<Arc<[u8]>, SerializationError> {
+        self.message.to_bytes()
+    }
+}
+
+impl<'a, M> SubscriberMessage<'a> for WithSchema<M>
+where
+    M: SubscriberMessage<'a> + SerdeMessage,
+    M::Payload: JsonSchema,
+{
+    fn datatype() -> DataTypeInfo {
+        DataTypeInfo {
+            descriptor: schema_descriptor::<M::Payload>(),
+            ..M::datatype()
+        }
+    }
+
+    /// Decodes the inner message.
+    ///
+    /// With the `validate` feature, the payload is first checked against the
+    /// publisher's schema (if it announced one) and rejected on mismatch.
+    fn from_bytes(bytes: &'a [u8], data_type_info: &DataTypeInfo) -> Option<Self> {
+        #[cfg(feature = "validate")]
+        if let Some(validator) = validation::validator(&data_type_info.descriptor) {
+            return validation::decode_valid::<M>(&validator, bytes).map(Self::new);
+        }
+        M::from_bytes(bytes, data_type_info).map(Self::new)
+    }
+}
+
+#[cfg(feature = "validate")]
+mod validation {
+    use crate::format_support::{FormatSupport, SerdeMessage};
+    use jsonschema::Validator;
+    use std::collections::HashMap;
+    use std::sync::{Arc, LazyLock, Mutex};
+
+    /// Number of distinct schemas kept compiled; the cache starts over beyond it.
+    const MAX_CACHED_SCHEMAS: usize = 64;
+
+    /// Compiled validators, keyed by the publisher's schema descriptor.
+    ///
+    /// `None` marks descriptors that are not a usable JSON Schema.
+    type ValidatorCache = Mutex<HashMap<Vec<u8>, Option<Arc<Validator>>>>;
+
+    static VALIDATORS: LazyLock<ValidatorCache> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+    /// Returns the validator for the schema in `descriptor`.
+    ///
+    /// `None` if the publisher did not announce a schema, or announced
+    /// something that is not a valid JSON Schema; such payloads are accepted
+    /// unchecked. Schemas are compiled once, outside the cache lock.
+    pub(super) fn validator(descriptor: &[u8]) -> Option<Arc<Validator>> {
+        if descriptor.is_empty() {
+            return None;
+        }
+        if let Some(cached) = VALIDATORS.lock().unwrap().get(descriptor) {
+            return cached.clone();
+        }
+
+        let compiled = serde_json::from_slice::<serde_json::Value>(descriptor)
+            .ok()
+            .and_then(|schema| jsonschema::validator_for(&schema).ok())
+            .map(Arc::new);
+
+        let mut cache = VALIDATORS.lock().unwrap();
+        if cache.len() >= MAX_CACHED_SCHEMAS {
+            cache.clear();
+        }
+        cache.insert(descriptor.to_vec(), compiled.clone());
+        compiled
+    }
+
+    /// Decodes `bytes` once, checks them against `validator` and converts
+    /// them into the message, or returns `None` if they do not conform.
+    pub(super) fn decode_valid<M: SerdeMessage>(validator: &Validator, bytes: &[u8]) -> Option<M> {
+        let instance = M::Format::decode::<serde_json::Value>(bytes)?;
+        if !validator.is_valid(&instance) {
+            return None;
+        }
+        serde_json::from_value(instance).ok().map(M::from_payload)
+    }
+}
@@ -21,3 +21,14 @@ fn short_type_name_for_nested_type() {
         "TestType"
     );
 }
+
+#[test]
+fn json_encode_error_is_reported() {
+    use rustecal_pubsub::PublisherMessage;
+    use rustecal_types_serde::JsonMessage;
+    use std::collections::HashMap;
+
+    // JSON object keys must be strings, so tuple keys cannot be encoded
+    let payload: HashMap<(u8, u8), u8> = HashMap::from([((1, 2), 3)]);
+    assert!(JsonMessage::new(payload).to_bytes().is_err());
+}
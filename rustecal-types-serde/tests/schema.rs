@@ -0,0 +1,86 @@
+#![cfg(feature = "schema")]
+
+use rustecal_pubsub::{PublisherMessage, SubscriberMessage};
+use rustecal_types_serde::{CborMessage, JsonMessage, MsgpackMessage, WithSchema};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug)]
+struct Sample {
+    message: String,
+    count: u64,
+}
+
+#[test]
+fn descriptor_contains_json_schema() {
+    let info = <WithSchema<JsonMessage<Sample>> as PublisherMessage>::datatype();
+    assert_eq!(info.encoding, "json");
+
+    let schema: serde_json::Value = serde_json::from_slice(&info.descriptor).unwrap();
+    assert_eq!(schema["title"], "Sample");
+    assert!(schema["properties"]["count"].is_object());
+}
+
+#[cfg(feature = "validate")]
+#[test]
+fn payload_is_validated_against_publisher_schema() {
+    type Generic = WithSchema<JsonMessage<serde_json::Value>>;
+    let publisher_info = <WithSchema<JsonMessage<Sample>> as PublisherMessage>::datatype();
+
+    let valid = br#"{"message":"hello","count":1}"#;
+    assert!(<Generic as SubscriberMessage>::from_bytes(valid, &publisher_info).is_some());
+
+    let invalid = br#"{"message":"hello","count":"one"}"#;
+    assert!(<Generic as SubscriberMessage>::from_bytes(invalid, &publisher_info).is_none());
+}
+
+#[cfg(feature = "validate")]
+#[derive(Serialize, Clone)]
+struct WrongSample {
+    message: String,
+    count: String,
+}
+
+#[cfg(feature = "validate")]
+fn wrong_sample() -> WrongSample {
+    WrongSample {
+        message: "hello".to_string(),
+        count: "one".to_string(),
+    }
+}
+
+#[cfg(feature = "validate")]
+fn sample() -> Sample {
+    Sample {
+        message: "hello".to_string(),
+        count: 1,
+    }
+}
+
+#[cfg(feature = "validate")]
+#[test]
+fn cbor_payload_is_validated() {
+    type Received = WithSchema<CborMessage<Sample>>;
+    let publisher_info = <Received as PublisherMessage>::datatype();
+
+    let valid = CborMessage::new(sample()).to_bytes().unwrap();
+    let received = <Received as SubscriberMessage>::from_bytes(&valid, &publisher_info).unwrap();
+    assert_eq!(received.data.count, 1);
+
+    let invalid = serde_cbor::to_vec(&wrong_sample()).unwrap();
+    assert!(<Received as SubscriberMessage>::from_bytes(&invalid, &publisher_info).is_none());
+}
+
+#[cfg(feature = "validate")]
+#[test]
+fn msgpack_payload_is_validated() {
+    type Received = WithSchema<MsgpackMessage<Sample>>;
+    let publisher_info = <Received as PublisherMessage>::datatype();
+
+    let valid = MsgpackMessage::new(sample()).to_bytes().unwrap();
+    let received = <Received as SubscriberMessage>::from_bytes(&valid, &publisher_info).unwrap();
+    assert_eq!(received.data.message, "hello");
+
+    let invalid = rmp_serde::to_vec_named(&wrong_sample()).unwrap();
+    assert!(<Received as SubscriberMessage>::from_bytes(&invalid, &publisher_info).is_none());
+}
@@ -0,0 +1,521 @@
+//! # rustecal-types-signed
+//!
+//! Provides an authenticated message envelope for any rustecal message type.
+//!
+//! [`Signed<M, K>`] appends a timestamp, the sender identity and a signature
+//! to the serialized bytes of `M` on send. On receive, the signature is
+//! checked with the key the [`KeyProvider`] `K` returns for the sender;
+//! unauthenticated, tampered or expired messages are dropped, counted in the
+//! subscriber's [`VerificationStats`] and reported through
+//! [`KeyProvider::on_rejected`].
+//!
+//! The signature also covers the topic, type name and encoding, so a message
+//! cannot be reposted on another topic or as another type using the same key.
+//! Together with the timestamp and [`KeyProvider::max_age`], this bounds how
+//! long a recorded message can be replayed.
+//!
+//! ## Features
+//!
+//! - `hmac-sha256` (default): HMAC-SHA256 with shared secrets
+//! - `ed25519` (default): Ed25519 signatures
+//!
+//! ## Wire Format
+//!
+//! ```text
+//! payload | timestamp: i64 LE | sender | sender_len: u16 LE | algorithm: u8 | signature | signature_len: u8
+//! ```
+//!
+//! `timestamp` is the signing time in microseconds since the Unix epoch. The
+//! signature covers `topic \0 type_name \0 encoding \0` followed by everything
+//! in front of it.
+
+#[cfg(not(any(feature = "hmac-sha256", feature = "ed25519")))]
+compile_error!("enable at least one of the `hmac-sha256` or `ed25519` features");
+
+use rustecal_core::types::DataTypeInfo;
+use rustecal_pubsub::error::SerializationError;
+use rustecal_pubsub::typed_publisher::{PublisherMessage, SendContext};
+use rustecal_pubsub::typed_subscriber::{ReceiveContext, SubscriberMessage};
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+#[cfg(feature = "hmac-sha256")]
+const ALGORITHM_HMAC_SHA256: u8 = 1;
+#[cfg(feature = "ed25519")]
+const ALGORITHM_ED25519: u8 = 2;
+
+/// Key used to sign published messages.
+#[derive(Clone)]
+pub enum SigningKey {
+    /// Shared secret for HMAC-SHA256.
+    #[cfg(feature = "hmac-sha256")]
+    HmacSha256(Vec<u8>),
+    /// Ed25519 private key.
+    #[cfg(feature = "ed25519")]
+    Ed25519(ed25519_dalek::SigningKey),
+}
+
+/// Key used to verify received messages of one sender.
+#[derive(Clone)]
+pub enum VerifyingKey {
+    /// Shared secret for HMAC-SHA256.
+    #[cfg(feature = "hmac-sha256")]
+    HmacSha256(Vec<u8>),
+    /// Ed25519 public key.
+    #[cfg(feature = "ed25519")]
+    Ed25519(ed25519_dalek::VerifyingKey),
+}
+
+/// Identity and key of the local publisher.
+#[derive(Clone)]
+pub struct SenderKey {
+    /// Sender identity, sent in clear with every message.
+    pub sender: String,
+    /// Key used to sign.
+    pub key: SigningKey,
+}
+
+/// Reason a received message was dropped.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum Rejection {
+    /// The message carries no valid signature envelope.
+    #[error("message is not signed or the envelope is malformed")]
+    Malformed,
+    /// No key is known for the sender.
+    #[error("unknown sender `{0}`")]
+    UnknownSender(String),
+    /// The signature does not match, e.g. because the message was tampered
+    /// with or reposted on another topic.
+    #[error("invalid signature from sender `{0}`")]
+    InvalidSignature(String),
+    /// The message is older than [`KeyProvider::max_age`], e.g. because it was
+    /// replayed, or its timestamp lies too far in the future.
+    #[error("expired message from sender `{0}`")]
+    Expired(String),
+}
+
+/// Supplies the keys for a [`Signed`] topic.
+///
+/// Keys are looked up per message, so they can be rotated at runtime.
+///
+/// ```ignore
+/// struct ActuatorKeys;
+///
+/// impl KeyProvider for ActuatorKeys {
+///     fn sender_key() -> Option<SenderKey> {
+///         Some(SenderKey { sender: "planner".into(), key: SigningKey::HmacSha256(SECRET.to_vec()) })
+///     }
+///
+///     fn verifying_key(sender: &str) -> Option<VerifyingKey> {
+///         (sender == "planner").then(|| VerifyingKey::HmacSha256(SECRET.to_vec()))
+///     }
+/// }
+/// ```
+pub trait KeyProvider: 'static {
+    /// Identity and key used to sign published messages.
+    ///
+    /// `None` if the local process may not publish.
+    fn sender_key() -> Option<SenderKey>;
+
+    /// Key to verify messages from `sender`, or `None` if the sender is not trusted.
+    fn verifying_key(sender: &str) -> Option<VerifyingKey>;
+
+    /// Called for every dropped message. Does nothing by default.
+    fn on_rejected(_rejection: &Rejection) {}
+
+    /// Maximum age of accepted messages, 10 s by default.
+    ///
+    /// Publisher and subscriber clocks must agree within this margin.
+    fn max_age() -> Duration {
+        Duration::from_secs(10)
+    }
+}
+
+/// Counters of the messages verified by one subscriber.
+///
+/// Attach them with `TypedSubscriber::set_receive_state`:
+///
+/// ```ignore
+/// let stats = Arc::new(VerificationStats::new());
+/// subscriber.set_receive_state(stats.clone());
+/// // ...
+/// println!("invalid signatures: {}", stats.invalid_signature());
+/// ```
+#[derive(Debug, Default)]
+pub struct VerificationStats {
+    accepted: AtomicU64,
+    malformed: AtomicU64,
+    unknown_sender: AtomicU64,
+    invalid_signature: AtomicU64,
+    expired: AtomicU64,
+}
+
+impl VerificationStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Messages with a valid signature.
+    pub fn accepted(&self) -> u64 {
+        self.accepted.load(Ordering::Relaxed)
+    }
+
+    /// Messages without a valid signature envelope.
+    pub fn malformed(&self) -> u64 {
+        self.malformed.load(Ordering::Relaxed)
+    }
+
+    /// Messages from senders without a key.
+    pub fn unknown_sender(&self) -> u64 {
+        self.unknown_sender.load(Ordering::Relaxed)
+    }
+
+    /// Messages whose signature did not match.
+    pub fn invalid_signature(&self) -> u64 {
+        self.invalid_signature.load(Ordering::Relaxed)
+    }
+
+    /// Messages older than the maximum age.
+    pub fn expired(&self) -> u64 {
+        self.expired.load(Ordering::Relaxed)
+    }
+
+    /// All dropped messages.
+    pub fn rejected(&self) -> u64 {
+        self.malformed() + self.unknown_sender() + self.invalid_signature() + self.expired()
+    }
+
+    fn record(&self, result: Result<(), &Rejection>) {
+        let counter = match result {
+            Ok(()) => &self.accepted,
+            Err(Rejection::Malformed) => &self.malformed,
+            Err(Rejection::UnknownSender(_)) => &self.unknown_sender,
+            Err(Rejection::InvalidSignature(_)) => &self.invalid_signature,
+            Err(Rejection::Expired(_)) => &self.expired,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Counts the result in the subscriber's stats, if any, and reports rejections to `K`.
+fn record<K: KeyProvider>(stats: Option<&VerificationStats>, result: Result<(), &Rejection>) {
+    if let Some(stats) = stats {
+        stats.record(result);
+    }
+    if let Err(rejection) = result {
+        K::on_rejected(rejection);
+    }
+}
+
+/// Current time in microseconds since the Unix epoch.
+fn now_micros() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_micros() as i64)
+}
+
+/// Prepends topic and data type to the signed part of an envelope, so a
+/// signature is only valid on the topic and as the type it was made for.
+fn signing_input(topic: &str, info: &DataTypeInfo, signed: &[u8]) -> Vec<u8> {
+    let mut input = Vec::with_capacity(
+        topic.len() + info.type_name.len() + info.encoding.len() + 3 + signed.len(),
+    );
+    for field in [topic, &info.type_name, &info.encoding] {
+        input.extend_from_slice(field.as_bytes());
+        input.push(0);
+    }
+    input.extend_from_slice(signed);
+    input
+}
+
+impl SigningKey {
+    fn algorithm(&self) -> u8 {
+        match self {
+            #[cfg(feature = "hmac-sha256")]
+            SigningKey::HmacSha256(_) => ALGORITHM_HMAC_SHA256,
+            #[cfg(feature = "ed25519")]
+            SigningKey::Ed25519(_) => ALGORITHM_ED25519,
+        }
+    }
+
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>, SerializationError> {
+        match self {
+            #[cfg(feature = "hmac-sha256")]
+            SigningKey::HmacSha256(secret) => {
+                use hmac::{KeyInit, Mac};
+
+                let mut mac = hmac::Hmac::<sha2::Sha256>::new_from_slice(secret)
+                    .map_err(SerializationError::new)?;
+                mac.update(data);
+                Ok(mac.finalize().into_bytes().to_vec())
+            }
+            #[cfg(feature = "ed25519")]
+            SigningKey::Ed25519(key) => {
+                use ed25519_dalek::Signer;
+
+                Ok(key.sign(data).to_bytes().to_vec())
+            }
+        }
+    }
+}
+
+impl VerifyingKey {
+    fn verify(&self, algorithm: u8, data: &[u8], signature: &[u8]) -> bool {
+        match self {
+            #[cfg(feature = "hmac-sha256")]
+            VerifyingKey::HmacSha256(secret) => {
+                use hmac::{KeyInit, Mac};
+
+                if algorithm != ALGORITHM_HMAC_SHA256 {
+                    return false;
+                }
+                let Ok(mut mac) = hmac::Hmac::<sha2::Sha256>::new_from_slice(secret) else {
+                    return false;
+                };
+                mac.update(data);
+                mac.verify_slice(signature).is_ok()
+            }
+            #[cfg(feature = "ed25519")]
+            VerifyingKey::Ed25519(key) => {
+                if algorithm != ALGORITHM_ED25519 {
+                    return false;
+                }
+                let Ok(signature) = ed25519_dalek::Signature::from_slice(signature) else {
+                    return false;
+                };
+                key.verify_strict(data, &signature).is_ok()
+            }
+        }
+    }
+}
+
+/// Wraps a message and authenticates it with the keys of `K`.
+///
+/// Derefs to the inner message, so `received.payload.data` keeps working.
+///
+/// ```ignore
+/// let publisher = TypedPublisher::<Signed<StringMessage, ActuatorKeys>>::new("actuator")?;
+/// publisher.send(&Signed::new(message), Timestamp::Auto);
+/// ```
+pub struct Signed<M, K> {
+    /// The wrapped message.
+    pub message: M,
+    sender: Option<String>,
+    _keys: PhantomData<K>,
+}
+
+impl<M, K: KeyProvider> Signed<M, K> {
+    /// Wraps the given message for sending.
+    pub fn new(message: M) -> Self {
+        Self {
+            message,
+            sender: None,
+            _keys: PhantomData,
+        }
+    }
+
+    /// Returns the verified sender identity of a received message.
+    ///
+    /// `None` for messages created with [`Signed::new`].
+    pub fn sender(&self) -> Option<&str> {
+        self.sender.as_deref()
+    }
+}
+
+// Manual impls, so key providers need not be `Clone`/`Debug` themselves
+impl<M: Clone, K> Clone for Signed<M, K> {
+    fn clone(&self) -> Self {
+        Self {
+            message: self.message.clone(),
+            sender: self.sender.clone(),
+            _keys: PhantomData,
+        }
+    }
+}
+
+impl<M: std::fmt::Debug, K> std::fmt::Debug for Signed<M, K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Signed")
+            .field("message", &self.message)
+            .field("sender", &self.sender)
+            .finish()
+    }
+}
+
+impl<M, K: KeyProvider> From<M> for Signed<M, K> {
+    fn from(message: M) -> Self {
+        Self::new(message)
+    }
+}
+
+impl<M, K> Deref for Signed<M, K> {
+    type Target = M;
+    fn deref(&self) -> &M {
+        &self.message
+    }
+}
+
+/// Appends the `signed` marker to the encoding of `info`.
+fn signed_info(info: DataTypeInfo) -> DataTypeInfo {
+    DataTypeInfo {
+        encoding: format!("{}+signed", info.encoding),
+        ..info
+    }
+}
+
+/// Envelope fields borrowed from a received message.
+struct Envelope<'a> {
+    payload: &'a [u8],
+    timestamp: i64,
+    sender: &'a str,
+    algorithm: u8,
+    signed: &'a [u8],
+    signature: &'a [u8],
+}
+
+impl<'a> Envelope<'a> {
+    fn parse(bytes: &'a [u8]) -> Option<Self> {
+        let (&signature_len, rest) = bytes.split_last()?;
+        let (signed, signature) =
+            rest.split_at_checked(rest.len().checked_sub(signature_len as usize)?)?;
+        let (&algorithm, rest) = signed.split_last()?;
+        let (rest, sender_len) = rest.split_at_checked(rest.len().checked_sub(2)?)?;
+        let sender_len = u16::from_le_bytes([sender_len[0], sender_len[1]]) as usize;
+        let (rest, sender) = rest.split_at_checked(rest.len().checked_sub(sender_len)?)?;
+        let (payload, timestamp) = rest.split_at_checked(rest.len().checked_sub(8)?)?;
+        Some(Self {
+            payload,
+            timestamp: i64::from_le_bytes(timestamp.try_into().ok()?),
+            sender: std::str::from_utf8(sender).ok()?,
+            algorithm,
+            signed,
+            signature,
+        })
+    }
+}
+
+impl<M, K> PublisherMessage for Signed<M, K>
+where
+    M: PublisherMessage,
+    K: KeyProvider,
+{
+    /// Returns the inner message metadata with `+signed` appended to the encoding.
+    fn datatype() -> DataTypeInfo {
+        signed_info(M::datatype())
+    }
+
+    /// Signed messages are bound to their topic, so they can only be
+    /// serialized through [`to_bytes_with`](Self::to_bytes_with).
+    ///
+    /// # Errors
+    /// Always returns a [`SerializationError`].
+    fn to_bytes(&self) -> Result<Arc<[u8]>, SerializationError> {
+        Err(SerializationError::new(
+            "signed messages need the topic, publish them with a TypedPublisher",
+        ))
+    }
+
+    /// Serializes the inner message and appends timestamp, sender identity
+    /// and a signature bound to the topic and data type.
+    ///
+    /// # Errors
+    /// Returns a [`SerializationError`] if the inner message cannot be
+    /// serialized or `K` provides no sender key.
+    fn to_bytes_with(&self, context: &SendContext<'_>) -> Result<Arc<[u8]>, SerializationError> {
+        let SenderKey { sender, key } =
+            K::sender_key().ok_or_else(|| SerializationError::new("no sender key available"))?;
+        let sender_len = u16::try_from(sender.len())
+            .map_err(|_| SerializationError::new("sender identity too long"))?;
+
+        let payload = self.message.to_bytes_with(context)?;
+        let mut bytes = Vec::with_capacity(payload.len() + 8 + sender.len() + 3 + 65);
+        bytes.extend_from_slice(&payload);
+        bytes.extend_from_slice(&now_micros().to_le_bytes());
+        bytes.extend_from_slice(sender.as_bytes());
+        bytes.extend_from_slice(&sender_len.to_le_bytes());
+        bytes.push(key.algorithm());
+
+        let info = <Self as PublisherMessage>::datatype();
+        let signature = key.sign(&signing_input(context.topic_name, &info, &bytes))?;
+        bytes.extend_from_slice(&signature);
+        bytes.push(signature.len() as u8);
+        Ok(Arc::from(bytes))
+    }
+}
+
+impl<'a, M, K> SubscriberMessage<'a> for Signed<M, K>
+where
+    M: SubscriberMessage<'a>,
+    K: KeyProvider,
+{
+    fn datatype() -> DataTypeInfo {
+        signed_info(M::datatype())
+    }
+
+    /// Signed messages can only be verified with the topic they were received
+    /// on, see [`from_bytes_with`](Self::from_bytes_with); this drops them.
+    fn from_bytes(_bytes: &'a [u8], _data_type_info: &DataTypeInfo) -> Option<Self> {
+        None
+    }
+
+    /// Verifies signature, topic, data type and age, then decodes the inner
+    /// message from the payload part.
+    ///
+    /// Results are counted in the [`VerificationStats`] attached to the
+    /// subscriber, and rejected messages are reported to `K::on_rejected`.
+    fn from_bytes_with(
+        bytes: &'a [u8],
+        data_type_info: &DataTypeInfo,
+        context: &ReceiveContext<'_>,
+    ) -> Option<Self> {
+        let stats = context.state::<VerificationStats>();
+        let Some(envelope) = Envelope::parse(bytes) else {
+            record::<K>(stats, Err(&Rejection::Malformed));
+            return None;
+        };
+        let Some(key) = K::verifying_key(envelope.sender) else {
+            record::<K>(
+                stats,
+                Err(&Rejection::UnknownSender(envelope.sender.to_string())),
+            );
+            return None;
+        };
+
+        // verify against the expected type, not the one the publisher announced
+        let info = <Self as SubscriberMessage>::datatype();
+        let input = signing_input(context.topic_name, &info, envelope.signed);
+        if !key.verify(envelope.algorithm, &input, envelope.signature) {
+            record::<K>(
+                stats,
+                Err(&Rejection::InvalidSignature(envelope.sender.to_string())),
+            );
+            return None;
+        }
+        let age = now_micros()
+            .saturating_sub(envelope.timestamp)
+            .unsigned_abs();
+        if age > K::max_age().as_micros() as u64 {
+            record::<K>(stats, Err(&Rejection::Expired(envelope.sender.to_string())));
+            return None;
+        }
+        record::<K>(stats, Ok(()));
+
+        let inner_info = DataTypeInfo {
+            encoding: data_type_info
+                .encoding
+                .strip_suffix("+signed")
+                .unwrap_or(&data_type_info.encoding)
+                .to_string(),
+            ..data_type_info.clone()
+        };
+        M::from_bytes_with(envelope.payload, &inner_info, context).map(|message| Self {
+            message,
+            sender: Some(envelope.sender.to_string()),
+            _keys: PhantomData,
+        })
+    }
+}
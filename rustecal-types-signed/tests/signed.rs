@@ -0,0 +1,199 @@
+#![cfg(all(feature = "hmac-sha256", feature = "ed25519"))]
+
+use rustecal_pubsub::{PublisherMessage, ReceiveContext, SendContext, SubscriberMessage};
+use rustecal_types_signed::{
+    KeyProvider, Rejection, SenderKey, Signed, SigningKey, VerificationStats, VerifyingKey,
+};
+use rustecal_types_string::StringMessage;
+use std::sync::Mutex;
+use std::time::Duration;
+
+const SECRET: &[u8] = b"actuator secret";
+
+struct HmacKeys;
+
+impl KeyProvider for HmacKeys {
+    fn sender_key() -> Option<SenderKey> {
+        Some(SenderKey {
+            sender: "planner".into(),
+            key: SigningKey::HmacSha256(SECRET.to_vec()),
+        })
+    }
+
+    fn verifying_key(sender: &str) -> Option<VerifyingKey> {
+        (sender == "planner").then(|| VerifyingKey::HmacSha256(SECRET.to_vec()))
+    }
+}
+
+fn ed25519_key() -> ed25519_dalek::SigningKey {
+    ed25519_dalek::SigningKey::from_bytes(&[7; 32])
+}
+
+static REJECTIONS: Mutex<Vec<Rejection>> = Mutex::new(Vec::new());
+
+struct Ed25519Keys;
+
+impl KeyProvider for Ed25519Keys {
+    fn sender_key() -> Option<SenderKey> {
+        Some(SenderKey {
+            sender: "planner".into(),
+            key: SigningKey::Ed25519(ed25519_key()),
+        })
+    }
+
+    fn verifying_key(sender: &str) -> Option<VerifyingKey> {
+        (sender == "planner").then(|| VerifyingKey::Ed25519(ed25519_key().verifying_key()))
+    }
+
+    fn on_rejected(rejection: &Rejection) {
+        REJECTIONS.lock().unwrap().push(rejection.clone());
+    }
+}
+
+/// Signs as an untrusted sender, verifies like `HmacKeys`.
+struct UnknownSenderKeys;
+
+impl KeyProvider for UnknownSenderKeys {
+    fn sender_key() -> Option<SenderKey> {
+        Some(SenderKey {
+            sender: "intruder".into(),
+            key: SigningKey::HmacSha256(b"guess".to_vec()),
+        })
+    }
+
+    fn verifying_key(sender: &str) -> Option<VerifyingKey> {
+        HmacKeys::verifying_key(sender)
+    }
+}
+
+/// Like `HmacKeys`, accepting messages for 50 ms only.
+struct ShortLivedKeys;
+
+impl KeyProvider for ShortLivedKeys {
+    fn sender_key() -> Option<SenderKey> {
+        HmacKeys::sender_key()
+    }
+
+    fn verifying_key(sender: &str) -> Option<VerifyingKey> {
+        HmacKeys::verifying_key(sender)
+    }
+
+    fn max_age() -> Duration {
+        Duration::from_millis(50)
+    }
+}
+
+const TOPIC: &str = "actuator";
+
+fn text(data: &str) -> StringMessage {
+    StringMessage { data: data.into() }
+}
+
+fn sign<K: KeyProvider>(data: &str, topic: &str) -> Vec<u8> {
+    Signed::<_, K>::new(text(data))
+        .to_bytes_with(&SendContext { topic_name: topic })
+        .unwrap()
+        .to_vec()
+}
+
+fn verify<K: KeyProvider>(
+    bytes: &[u8],
+    topic: &str,
+    stats: &VerificationStats,
+) -> Option<Signed<StringMessage, K>> {
+    let info = <Signed<StringMessage, K> as SubscriberMessage>::datatype();
+    let context = ReceiveContext::new(topic).with_state(stats);
+    <Signed<StringMessage, K> as SubscriberMessage>::from_bytes_with(bytes, &info, &context)
+}
+
+#[test]
+fn datatype_marks_encoding() {
+    let info = <Signed<StringMessage, HmacKeys> as PublisherMessage>::datatype();
+    assert_eq!(info.encoding, "utf-8+signed");
+    assert_eq!(info.type_name, "string");
+}
+
+#[test]
+fn hmac_round_trip() {
+    let stats = VerificationStats::new();
+    let received =
+        verify::<HmacKeys>(&sign::<HmacKeys>("open valve", TOPIC), TOPIC, &stats).unwrap();
+    assert_eq!(&*received.data, "open valve");
+    assert_eq!(received.sender(), Some("planner"));
+    assert_eq!(stats.accepted(), 1);
+}
+
+#[test]
+fn signing_needs_topic() {
+    assert!(
+        Signed::<_, HmacKeys>::new(text("open valve"))
+            .to_bytes()
+            .is_err()
+    );
+}
+
+#[test]
+fn ed25519_rejects_tampering() {
+    let stats = VerificationStats::new();
+    let bytes = sign::<Ed25519Keys>("open valve", TOPIC);
+    assert!(verify::<Ed25519Keys>(&bytes, TOPIC, &stats).is_some());
+
+    let mut tampered = bytes.clone();
+    tampered[0] = b'O';
+    assert!(verify::<Ed25519Keys>(&tampered, TOPIC, &stats).is_none());
+
+    // plain, unsigned message
+    assert!(verify::<Ed25519Keys>(b"", TOPIC, &stats).is_none());
+
+    assert_eq!(stats.accepted(), 1);
+    assert_eq!(stats.malformed(), 1);
+    assert_eq!(stats.unknown_sender(), 0);
+    assert_eq!(stats.invalid_signature(), 1);
+    assert_eq!(stats.rejected(), 2);
+    assert_eq!(
+        *REJECTIONS.lock().unwrap(),
+        vec![
+            Rejection::InvalidSignature("planner".into()),
+            Rejection::Malformed
+        ]
+    );
+}
+
+#[test]
+fn unknown_sender_is_rejected() {
+    let stats = VerificationStats::new();
+    let bytes = sign::<UnknownSenderKeys>("open valve", TOPIC);
+    assert!(verify::<UnknownSenderKeys>(&bytes, TOPIC, &stats).is_none());
+    assert_eq!(stats.unknown_sender(), 1);
+}
+
+#[test]
+fn stats_are_kept_per_subscriber() {
+    let first = VerificationStats::new();
+    let second = VerificationStats::new();
+    let bytes = sign::<HmacKeys>("open valve", TOPIC);
+
+    assert!(verify::<HmacKeys>(&bytes, TOPIC, &first).is_some());
+    assert!(verify::<HmacKeys>(b"", TOPIC, &first).is_none());
+    assert_eq!((first.accepted(), first.malformed()), (1, 1));
+    assert_eq!((second.accepted(), second.rejected()), (0, 0));
+}
+
+#[test]
+fn cross_topic_repost_is_rejected() {
+    let stats = VerificationStats::new();
+    let bytes = sign::<HmacKeys>("open valve", TOPIC);
+    assert!(verify::<HmacKeys>(&bytes, "brake", &stats).is_none());
+    assert_eq!(stats.invalid_signature(), 1);
+}
+
+#[test]
+fn replay_is_rejected() {
+    let stats = VerificationStats::new();
+    let bytes = sign::<ShortLivedKeys>("open valve", TOPIC);
+    assert!(verify::<ShortLivedKeys>(&bytes, TOPIC, &stats).is_some());
+
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(verify::<ShortLivedKeys>(&bytes, TOPIC, &stats).is_none());
+    assert_eq!(stats.expired(), 1);
+}
@@ -3,6 +3,7 @@
 //! Provides support for sending and receiving `String` messages with rustecal.
 
 use rustecal_core::types::DataTypeInfo;
+use rustecal_pubsub::error::SerializationError;
 use rustecal_pubsub::typed_publisher::PublisherMessage;
 use rustecal_pubsub::typed_subscriber::SubscriberMessage;
 use std::str;
@@ -41,7 +42,7 @@ impl PublisherMessage for StringMessage {
     }
 
     /// Serializes the string into a byte buffer.
-    fn to_bytes(&self) -> Arc<[u8]> {
-        Arc::from(self.data.as_bytes())
+    fn to_bytes(&self) -> Result<Arc<[u8]>, SerializationError> {
+        Ok(Arc::from(self.data.as_bytes()))
     }
 }
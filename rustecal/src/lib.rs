@@ -36,6 +36,8 @@ pub use rustecal_pubsub::{
     // low‑level handles
     Publisher,
     PublisherMessage,
+    ReceiveContext,
+    SendContext,
     Subscriber,
     SubscriberMessage,
     // typed wrappers
@@ -43,6 +45,9 @@ pub use rustecal_pubsub::{
     TypedSubscriber,
 };
 
+#[cfg(feature = "derive")]
+pub use rustecal_pubsub::EcalMessage;
+
 // —————————————————————————————————————————————————————————————————————————————
 // Service RPC API (requires the `service` feature)
 #[cfg(feature = "service")]
@@ -53,19 +58,37 @@ pub mod service {
 
 #[cfg(feature = "service")]
 pub use rustecal_service::{
+    CallError,
+    // call policies
+    CallPolicy,
+    ClientEvent,
+    ClientEventKind,
     ClientInstance,
+    MethodError,
+    PolicyClient,
+    RetryPolicy,
+    Selection,
+    // connection events
+    ServerEvent,
+    ServerEventKind,
     ServiceClient,
+    ServiceId,
     // request/response types
     ServiceRequest,
     ServiceResponse,
     // server & client entrypoints
     ServiceServer,
+    // typed server & client
+    TypedResponse,
+    TypedServiceClient,
+    TypedServiceServer,
 };
 
 #[cfg(feature = "service")]
 pub use rustecal_service::types::{
     CallState,
     // metadata & callback signature
+    FallibleServiceCallback,
     MethodInfo,
     ServiceCallback,
 };